@@ -0,0 +1,57 @@
+//! Forms that post to server functions and work without javascript.
+//!
+//! A form with an action from [`form_action`] posts to the page it is rendered on. If the render handler responds to `POST` with
+//! [`MethodBehavior::ServerFnForm`](crate::prelude::MethodBehavior::ServerFnForm), the server runs the server function with the
+//! fields of the form and then renders the page again. With javascript, the client can still intercept the submit event and call
+//! the server function directly.
+
+/// The query parameter of the form action that names the server function a form posts to
+pub const FORM_SERVER_FN_PARAM: &str = "dioxus-server-fn";
+
+/// Get the `action` of a `<form method="post">` that runs the server function `F` and then renders `page`. The names of the inputs
+/// of the form must match the arguments of the server function, and the server function must use the default `PostUrl` input encoding.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// #[server]
+/// async fn subscribe(email: String) -> Result<(), ServerFnError> {
+///     Ok(())
+/// }
+///
+/// fn app() -> Element {
+///     rsx! {
+///         form { method: "post", action: form_action::<Subscribe>("/newsletter"),
+///             input { name: "email", r#type: "email" }
+///             button { "Subscribe" }
+///         }
+///     }
+/// }
+/// ```
+pub fn form_action<F: server_fn::ServerFn>(page: &str) -> String {
+    let separator = if page.contains('?') { '&' } else { '?' };
+    format!("{page}{separator}{FORM_SERVER_FN_PARAM}={}", F::PATH)
+}
+
+/// Split the server function a form posted to out of the query of the page. Returns the path of the server function and the uri of the page
+/// without the parameter.
+#[cfg(feature = "axum")]
+pub(crate) fn split_form_action(uri: &http::Uri) -> Option<(String, http::Uri)> {
+    let query = uri.query()?;
+    let mut server_fn = None;
+    let mut rest = Vec::new();
+    for param in query.split('&') {
+        match param.split_once('=') {
+            Some((FORM_SERVER_FN_PARAM, path)) => server_fn = Some(path.to_string()),
+            _ => rest.push(param),
+        }
+    }
+    let server_fn = server_fn?;
+    let page = match rest.is_empty() {
+        true => uri.path().to_string(),
+        false => format!("{}?{}", uri.path(), rest.join("&")),
+    };
+    Some((server_fn, page.parse().ok()?))
+}
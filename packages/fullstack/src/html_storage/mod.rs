@@ -55,15 +55,31 @@ impl HTMLData {
 
     /// Insert data into an entry that was created with [`Self::create_entry`]
     pub(crate) fn insert<T: Serialize>(&mut self, id: usize, value: &T) {
-        let mut serialized = Vec::new();
-        ciborium::into_writer(value, &mut serialized).unwrap();
-        self.data[id] = Some(serialized);
+        self.data[id] = serialize_entry(value);
     }
 
     /// Push resolved data into the serialized server data
     pub(crate) fn push<T: Serialize>(&mut self, data: &T) {
-        let mut serialized = Vec::new();
-        ciborium::into_writer(data, &mut serialized).unwrap();
-        self.data.push(Some(serialized));
+        self.data.push(serialize_entry(data));
+    }
+}
+
+/// Serialize the data of an entry. Data that contains a [`Secret`](crate::prelude::Secret) is never sent to the client
+fn serialize_entry<T: Serialize>(value: &T) -> Option<Vec<u8>> {
+    let mut serialized = Vec::new();
+    let leaked = crate::secret::serialize_hydration_data(|| {
+        ciborium::into_writer(value, &mut serialized).unwrap();
+    });
+    if leaked {
+        if cfg!(debug_assertions) {
+            panic!(
+                "A `Secret` was serialized into the hydration data that is sent to the client. Remove it from the value returned by `use_server_future` or `use_server_cached`"
+            );
+        }
+        tracing::error!(
+            "Dropped hydration data that contains a `Secret` instead of sending it to the client"
+        );
+        return None;
     }
+    Some(serialized)
 }
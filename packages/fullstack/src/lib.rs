@@ -14,10 +14,26 @@ pub mod server;
 
 mod hooks;
 
+mod query_cache;
+
+mod variants;
+
+mod theme;
+
+mod ndjson;
+
+mod progress;
+
+mod forms;
+
+mod secret;
+
 pub mod document;
 #[cfg(feature = "server")]
 mod render;
 #[cfg(feature = "server")]
+mod render_logs;
+#[cfg(feature = "server")]
 mod streaming;
 
 #[cfg(feature = "server")]
@@ -28,11 +44,39 @@ pub use serve_config::*;
 #[cfg(feature = "server")]
 mod server_context;
 
+#[cfg(feature = "server")]
+mod trace_context;
+
+#[cfg(feature = "server")]
+mod sources;
+#[cfg(feature = "server")]
+pub use sources::*;
+
 /// A prelude of commonly used items in dioxus-fullstack.
 pub mod prelude {
     use crate::hooks;
     pub use hooks::{server_cached::use_server_cached, server_future::use_server_future};
 
+    pub use crate::query_cache::{use_query_cache, QueryCache};
+
+    pub use crate::variants::{use_variants, Variants};
+
+    pub use crate::theme::use_theme;
+
+    pub use crate::ndjson::ndjson_items;
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::ndjson::ndjson_stream;
+
+    pub use crate::forms::{form_action, FORM_SERVER_FN_PARAM};
+
+    pub use crate::secret::Secret;
+
+    pub use crate::progress::{progress_events, ProgressEvent};
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::progress::{progress_stream, ProgressSink};
+
     #[cfg(feature = "axum")]
     #[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
     pub use crate::server::*;
@@ -41,21 +85,29 @@ pub mod prelude {
     #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
     pub use crate::render::{FullstackHTMLTemplate, SSRState};
 
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::render_logs::RenderLogs;
+
     #[cfg(feature = "server")]
     #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
     pub use crate::serve_config::{ServeConfig, ServeConfigBuilder};
 
     #[cfg(all(feature = "server", feature = "axum"))]
     #[cfg_attr(docsrs, doc(cfg(all(feature = "server", feature = "axum"))))]
-    pub use crate::server_context::Axum;
+    pub use crate::server_context::{Axum, FormRejection};
 
     #[cfg(feature = "server")]
     #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
     pub use crate::server_context::{
         extract, server_context, with_server_context, DioxusServerContext, FromContext,
-        FromServerContext, ProvideServerContext,
+        FromServerContext, ProvideServerContext, RenderOutputs,
     };
 
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::trace_context::TraceContext;
+
     #[cfg(feature = "server")]
     #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
     pub use dioxus_isrg::{IncrementalRenderer, IncrementalRendererConfig};
@@ -0,0 +1,125 @@
+//! Stream newline delimited JSON (NDJSON) from server functions.
+//!
+//! Server functions that return large result sets can stream them one row at a time with the `StreamingText` output encoding.
+//! On the server, [`ndjson_stream`] serializes each item as a line of JSON. On the client, [`ndjson_items`] parses the lines
+//! back into items as they arrive.
+//!
+//! # Example
+//! ```rust, no_run
+//! # use dioxus::prelude::*;
+//! use server_fn::codec::{StreamingText, TextStream};
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Row {
+//!     id: usize,
+//! }
+//!
+//! #[server(output = StreamingText)]
+//! async fn rows() -> Result<TextStream, ServerFnError> {
+//!     let rows = futures_util::stream::iter((0..1000).map(|id| Ok::<_, ServerFnError>(Row { id })));
+//!     Ok(ndjson_stream(rows))
+//! }
+//!
+//! async fn print_rows() -> Result<(), ServerFnError> {
+//!     use futures_util::StreamExt;
+//!
+//!     let mut rows = std::pin::pin!(ndjson_items::<Row>(rows().await?));
+//!     while let Some(row) = rows.next().await {
+//!         println!("{}", row?.id);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use server_fn::codec::TextStream;
+use server_fn::ServerFnError;
+
+/// The content type of newline delimited JSON
+#[allow(unused)]
+pub(crate) const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Serialize a stream of items as newline delimited JSON for a server function that uses the `StreamingText` output encoding. Each
+/// item is sent to the client as soon as it is ready.
+///
+/// This sets the `Content-Type` of the response to `application/x-ndjson`. The [`DioxusServerContext`](crate::prelude::DioxusServerContext)
+/// of the request is available while the stream is polled. If the client disconnects, the stream is dropped which cancels the producer.
+#[cfg(feature = "server")]
+pub fn ndjson_stream<T, E>(items: impl Stream<Item = Result<T, E>> + Send + 'static) -> TextStream
+where
+    T: serde::Serialize,
+    E: std::fmt::Display,
+{
+    let server_context = crate::prelude::server_context();
+    server_context.response_parts_mut().headers.insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static(NDJSON_CONTENT_TYPE),
+    );
+
+    let lines = items.map(|item| match item {
+        Ok(item) => serde_json::to_string(&item)
+            .map(|mut line| {
+                line.push('\n');
+                line
+            })
+            .map_err(|err| ServerFnError::Serialization(err.to_string())),
+        Err(err) => Err(ServerFnError::ServerError(err.to_string())),
+    });
+
+    TextStream::new(ProvideServerContextStream {
+        context: server_context,
+        stream: Box::pin(lines),
+    })
+}
+
+/// Parse a stream of newline delimited JSON that was created with [`ndjson_stream`] into items. Items are yielded as soon as
+/// a full line is received.
+pub fn ndjson_items<T: DeserializeOwned>(
+    stream: TextStream,
+) -> impl Stream<Item = Result<T, ServerFnError>> {
+    stream
+        .into_inner()
+        .scan(String::new(), |buffer, chunk| {
+            let items = match chunk {
+                Ok(chunk) => {
+                    buffer.push_str(&chunk);
+                    let mut items = Vec::new();
+                    while let Some(newline) = buffer.find('\n') {
+                        let line: String = buffer.drain(..=newline).collect();
+                        let line = line.trim();
+                        if !line.is_empty() {
+                            items
+                                .push(serde_json::from_str(line).map_err(|err| {
+                                    ServerFnError::Deserialization(err.to_string())
+                                }));
+                        }
+                    }
+                    items
+                }
+                Err(err) => vec![Err(err)],
+            };
+            futures_util::future::ready(Some(futures_util::stream::iter(items)))
+        })
+        .flatten()
+}
+
+/// A stream that provides the server context while the inner stream is polled
+#[cfg(feature = "server")]
+struct ProvideServerContextStream<S> {
+    context: crate::prelude::DioxusServerContext,
+    stream: std::pin::Pin<Box<S>>,
+}
+
+#[cfg(feature = "server")]
+impl<S: Stream> Stream for ProvideServerContextStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let context = self.context.clone();
+        crate::prelude::with_server_context(context, || self.stream.poll_next_unpin(cx))
+    }
+}
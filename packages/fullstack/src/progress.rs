@@ -0,0 +1,194 @@
+//! Report the progress of a long running server function to the client while it runs.
+//!
+//! On the server, [`progress_stream`] runs a job and streams every event the job sends to the
+//! [`DioxusServerContext::progress_sink`](crate::prelude::DioxusServerContext::progress_sink) as newline delimited JSON, followed
+//! by the result of the job. On the client, [`progress_events`] parses the stream back into [`ProgressEvent`]s as they arrive.
+//!
+//! # Example
+//! ```rust, no_run
+//! # use dioxus::prelude::*;
+//! use server_fn::codec::{StreamingText, TextStream};
+//!
+//! #[server(output = StreamingText)]
+//! async fn import() -> Result<TextStream, ServerFnError> {
+//!     Ok(progress_stream(async move {
+//!         let sink = server_context().progress_sink();
+//!         for row in 0..100 {
+//!             // Import the row here
+//!             sink.send(&row);
+//!         }
+//!         Ok::<_, ServerFnError>("Imported 100 rows".to_string())
+//!     }))
+//! }
+//!
+//! async fn print_progress() -> Result<(), ServerFnError> {
+//!     use futures_util::StreamExt;
+//!
+//!     let mut events = std::pin::pin!(progress_events::<usize, String>(import().await?));
+//!     while let Some(event) = events.next().await {
+//!         match event? {
+//!             ProgressEvent::Progress(row) => println!("Imported row {row}"),
+//!             ProgressEvent::Done(message) => println!("{message}"),
+//!             ProgressEvent::Error(err) => println!("Import failed: {err}"),
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use futures_util::Stream;
+use serde::de::DeserializeOwned;
+use server_fn::codec::TextStream;
+use server_fn::ServerFnError;
+
+/// An event of a server function that streams its progress with [`progress_stream`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressEvent<P, T> {
+    /// The job reported progress
+    Progress(P),
+    /// The job finished. This is always the last event
+    Done(T),
+    /// The job failed. This is always the last event
+    Error(String),
+}
+
+/// Parse a stream that was created with [`progress_stream`] into events. Events are yielded as soon as they are received.
+pub fn progress_events<P: DeserializeOwned, T: DeserializeOwned>(
+    stream: TextStream,
+) -> impl Stream<Item = Result<ProgressEvent<P, T>, ServerFnError>> {
+    crate::prelude::ndjson_items(stream)
+}
+
+/// Run a job and stream the events it sends to the [`ProgressSink`] of the request, followed by the result of the job. Use this in
+/// a server function with the `StreamingText` output encoding.
+///
+/// This sets the `Content-Type` of the response to `application/x-ndjson`. The [`DioxusServerContext`](crate::prelude::DioxusServerContext)
+/// of the request is available while the job runs. If the client disconnects, the job is dropped and the
+/// [`cancellation_token`](crate::prelude::DioxusServerContext::cancellation_token) of the request is cancelled.
+#[cfg(feature = "server")]
+pub fn progress_stream<T, E, F>(job: F) -> TextStream
+where
+    F: std::future::Future<Output = Result<T, E>> + Send + 'static,
+    T: serde::Serialize,
+    E: std::fmt::Display,
+{
+    let server_context = crate::prelude::server_context();
+    server_context.response_parts_mut().headers.insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static(crate::ndjson::NDJSON_CONTENT_TYPE),
+    );
+
+    let (sender, progress) = futures_channel::mpsc::unbounded();
+    server_context.insert(ProgressSink {
+        sender: Some(sender),
+    });
+
+    TextStream::new(ProgressStream {
+        context: server_context,
+        job: Some(Box::pin(job)),
+        progress,
+        done: None,
+    })
+}
+
+/// Sends progress events to the client of a server function. Get the sink of the current request with
+/// [`DioxusServerContext::progress_sink`](crate::prelude::DioxusServerContext::progress_sink).
+///
+/// Events are only sent if the server function streams its progress with [`progress_stream`]. Otherwise, they are dropped.
+#[cfg(feature = "server")]
+#[derive(Clone, Default)]
+pub struct ProgressSink {
+    sender: Option<futures_channel::mpsc::UnboundedSender<String>>,
+}
+
+#[cfg(feature = "server")]
+impl ProgressSink {
+    /// Send a progress event to the client. Returns `false` if the event could not be sent, for example because the client disconnected.
+    pub fn send<P: serde::Serialize>(&self, progress: &P) -> bool {
+        let Some(sender) = &self.sender else {
+            return false;
+        };
+        match event_line(&ProgressEvent::<&P, ()>::Progress(progress)) {
+            Ok(line) => sender.unbounded_send(line).is_ok(),
+            Err(err) => {
+                tracing::error!("Failed to serialize progress event: {err}");
+                false
+            }
+        }
+    }
+}
+
+/// Serialize an event as a line of JSON
+#[cfg(feature = "server")]
+fn event_line<P: serde::Serialize, T: serde::Serialize>(
+    event: &ProgressEvent<P, T>,
+) -> Result<String, ServerFnError> {
+    serde_json::to_string(event)
+        .map(|mut line| {
+            line.push('\n');
+            line
+        })
+        .map_err(|err| ServerFnError::Serialization(err.to_string()))
+}
+
+/// A stream that runs a job and yields its progress events and then its result
+#[cfg(feature = "server")]
+struct ProgressStream<F> {
+    context: crate::prelude::DioxusServerContext,
+    job: Option<std::pin::Pin<Box<F>>>,
+    progress: futures_channel::mpsc::UnboundedReceiver<String>,
+    done: Option<Result<String, ServerFnError>>,
+}
+
+#[cfg(feature = "server")]
+impl<T, E, F> Stream for ProgressStream<F>
+where
+    F: std::future::Future<Output = Result<T, E>>,
+    T: serde::Serialize,
+    E: std::fmt::Display,
+{
+    type Item = Result<String, ServerFnError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use futures_util::StreamExt;
+        use std::future::Future;
+        use std::task::Poll;
+
+        let this = &mut *self;
+        loop {
+            // Send progress that is already queued before the result of the job
+            if let Poll::Ready(Some(line)) = this.progress.poll_next_unpin(cx) {
+                return Poll::Ready(Some(Ok(line)));
+            }
+
+            let Some(job) = &mut this.job else {
+                return Poll::Ready(this.done.take());
+            };
+            let context = this.context.clone();
+            match crate::prelude::with_server_context(context, || job.as_mut().poll(cx)) {
+                Poll::Ready(result) => {
+                    this.job = None;
+                    this.done = Some(match result {
+                        Ok(result) => event_line(&ProgressEvent::<(), T>::Done(result)),
+                        Err(err) => event_line(&ProgressEvent::<(), ()>::Error(err.to_string())),
+                    });
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl<F> Drop for ProgressStream<F> {
+    fn drop(&mut self) {
+        // The client disconnected before the job finished
+        if self.job.is_some() {
+            self.context.cancellation_token().cancel();
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! A cache of query results that is filled on the server before rendering and sent to the client with the hydration data.
+//!
+//! On the server, the cache is provided to the root of the app for each request. Prefetched queries can be added to the
+//! cache with [`ServeConfigBuilder::prefetch_queries`](crate::prelude::ServeConfigBuilder::prefetch_queries) before the render
+//! starts. After the initial render, the cache is serialized into the page next to the rest of the hydration data so the client
+//! can read the same results without fetching them again.
+
+use base64::Engine;
+use dioxus_lib::prelude::{try_consume_context, use_hook, ScopeId};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// The id of the element the query cache is serialized into
+#[allow(unused)]
+pub(crate) const QUERY_CACHE_ID: &str = "dioxus-query-cache";
+
+/// A shared cache of serialized query results keyed by a query key.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app() -> Element {
+///     let cache = use_query_cache();
+///     // If the server prefetched the query, this will not run the query again on the server or the client
+///     let user: String = cache.get("user").unwrap_or_else(|| "anonymous".to_string());
+///
+///     rsx! { "Hello {user}" }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct QueryCache {
+    entries: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl QueryCache {
+    /// Create a new empty query cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert the result of a query into the cache. This will replace any existing result for the same key.
+    ///
+    /// If the value fails to serialize, the error is logged and the cache is left unchanged.
+    pub fn insert<T: Serialize>(&self, key: impl Into<String>, value: &T) {
+        let key = key.into();
+        let mut serialized = Vec::new();
+        if let Err(err) = ciborium::into_writer(value, &mut serialized) {
+            tracing::error!("Failed to serialize the query {key:?} for the query cache: {err}");
+            return;
+        }
+        self.entries.write().unwrap().insert(key, serialized);
+    }
+
+    /// Get the result of a query from the cache. Returns `None` if the query is not cached or if it cannot be deserialized as `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let entries = self.entries.read().unwrap();
+        let serialized = entries.get(key)?;
+        ciborium::from_reader(serialized.as_slice()).ok()
+    }
+
+    /// Check if a query is in the cache
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.read().unwrap().contains_key(key)
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().unwrap().is_empty()
+    }
+
+    /// Encode the cache as base64. This is intended to be used in the server to send the cache to the client. Returns `None` and
+    /// logs the error if the cache fails to serialize.
+    #[cfg(feature = "server")]
+    pub(crate) fn serialized(&self) -> Option<String> {
+        let entries = self.entries.read().unwrap();
+        let mut serialized = Vec::new();
+        if let Err(err) = ciborium::into_writer(&*entries, &mut serialized) {
+            tracing::error!("Failed to serialize the query cache: {err}");
+            return None;
+        }
+        Some(base64::engine::general_purpose::STANDARD.encode(serialized))
+    }
+
+    /// Decode a cache that was serialized on the server with [`Self::serialized`]
+    #[allow(unused)]
+    pub(crate) fn deserialize(data: &str) -> Option<Self> {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .ok()?;
+        let entries: HashMap<String, Vec<u8>> = ciborium::from_reader(decoded.as_slice()).ok()?;
+        Some(Self {
+            entries: Arc::new(RwLock::new(entries)),
+        })
+    }
+
+    /// Read the cache the server serialized into the page
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    fn from_document() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let element = document.get_element_by_id(QUERY_CACHE_ID)?;
+        Self::deserialize(&element.text_content()?)
+    }
+}
+
+/// Get the [`QueryCache`] for the current app. On the server, this is the cache for the current request. On the client, this is
+/// the cache the server serialized into the page during the initial render.
+pub fn use_query_cache() -> QueryCache {
+    use_hook(query_cache)
+}
+
+pub(crate) fn query_cache() -> QueryCache {
+    if let Some(cache) = try_consume_context::<QueryCache>() {
+        return cache;
+    }
+
+    #[allow(unused_mut)]
+    let mut cache = QueryCache::default();
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    if let Some(from_document) = QueryCache::from_document() {
+        cache = from_document;
+    }
+
+    ScopeId::ROOT.provide_context(cache)
+}
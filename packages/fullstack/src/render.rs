@@ -1,9 +1,14 @@
 //! A shared pool of renderers for efficient server side rendering.
 use crate::document::ServerDocument;
+use crate::query_cache::QUERY_CACHE_ID;
 use crate::streaming::{Mount, StreamingRenderer};
+use crate::theme::THEME_ATTRIBUTE;
+use crate::variants::VARIANTS_ID;
+use crate::{EmptyRenderPolicy, HydrationCheck};
 use dioxus_interpreter_js::INITIALIZE_STREAMING_JS;
 use dioxus_isrg::{CachedRender, RenderFreshness};
 use dioxus_lib::document::Document;
+use dioxus_ssr::tags::{escape_attribute, Token, Tokens};
 use dioxus_ssr::Renderer;
 use futures_channel::mpsc::Sender;
 use futures_util::{Stream, StreamExt};
@@ -23,7 +28,9 @@ struct PendingSuspenseBoundary {
 }
 
 /// Spawn a task in the background. If wasm is enabled, this will use the single threaded tokio runtime
-fn spawn_platform<Fut>(f: impl FnOnce() -> Fut + Send + 'static) -> JoinHandle<Fut::Output>
+pub(crate) fn spawn_platform<Fut>(
+    f: impl FnOnce() -> Fut + Send + 'static,
+) -> JoinHandle<Fut::Output>
 where
     Fut: Future + 'static,
     Fut::Output: Send + 'static,
@@ -69,7 +76,7 @@ impl SsrRendererPool {
         &self,
         route: &str,
         render_into: &mut Sender<Result<String, dioxus_isrg::IncrementalRendererError>>,
-    ) -> Option<RenderFreshness> {
+    ) -> Option<(RenderFreshness, PreloadLinks)> {
         if let Some(incremental) = &self.incremental_cache {
             if let Ok(mut incremental) = incremental.write() {
                 match incremental.get(route) {
@@ -77,12 +84,13 @@ impl SsrRendererPool {
                         let CachedRender {
                             freshness,
                             response,
+                            preloads,
                             ..
                         } = cached_render;
                         _ = render_into.start_send(String::from_utf8(response.to_vec()).map_err(
                             |err| dioxus_isrg::IncrementalRendererError::Other(Box::new(err)),
                         ));
-                        return Some(freshness);
+                        return Some((freshness, PreloadLinks(preloads.to_vec())));
                     }
                     Err(e) => {
                         tracing::error!(
@@ -142,8 +150,29 @@ impl SsrRendererPool {
             Result<String, dioxus_isrg::IncrementalRendererError>,
         >(1000);
 
+        // Crawlers that don't run javascript get the page without any hydration data
+        let strip_hydration = cfg.strips_hydration_for(&server_context.request_parts());
+        // Pages stripped for crawlers and personalized pages never go through the incremental cache
+        let bypass_cache = (strip_hydration
+            && !cfg.is_static_page(server_context.request_parts().uri.path()))
+            || cfg.is_authenticated(&server_context.request_parts())
+            || server_context
+                .get::<Variants>()
+                .is_some_and(|variants| !variants.is_empty())
+            || server_context
+                .theme()
+                .is_some_and(|theme| !cfg.is_default_theme(&theme))
+            || cfg.bypasses_cache_for(&server_context.request_parts());
+
         // before we even spawn anything, we can check synchronously if we have the route cached
-        if let Some(freshness) = self.check_cached_route(&route, &mut into) {
+        let cached = if bypass_cache {
+            None
+        } else {
+            self.check_cached_route(&route, &mut into)
+        };
+        if let Some((freshness, preloads)) = cached {
+            server_context.insert(CacheHit);
+            server_context.insert(preloads);
             return Ok((
                 freshness,
                 ReceiverWithDrop {
@@ -153,7 +182,14 @@ impl SsrRendererPool {
             ));
         }
 
-        let wrapper = FullstackHTMLTemplate { cfg: cfg.clone() };
+        let wrapper = FullstackHTMLTemplate {
+            cfg: cfg.clone(),
+            strip_hydration,
+            locale: server_context.locale(),
+            theme: server_context.theme(),
+        };
+        let preloads = wrapper.preload_links();
+        server_context.insert(PreloadLinks(preloads.clone()));
 
         let server_context = server_context.clone();
         let mut renderer = self
@@ -165,7 +201,14 @@ impl SsrRendererPool {
 
         let myself = self.clone();
 
-        let join_handle = spawn_platform(move || async move {
+        // Collect the logs of the render in a subscriber that only this render uses
+        let collector = cfg.capture_render_logs.map(|level| {
+            let logs = crate::render_logs::RenderLogs::default();
+            server_context.insert(logs.clone());
+            logs.collector(level)
+        });
+
+        let render = move || async move {
             let mut virtual_dom = virtual_dom_factory();
             let document = std::rc::Rc::new(crate::document::server::ServerDocument::default());
             virtual_dom.provide_root_context(document.clone());
@@ -173,11 +216,55 @@ impl SsrRendererPool {
                 dioxus_history::MemoryHistory::with_initial_path(&route),
             ) as Rc<dyn dioxus_history::History>);
             virtual_dom.provide_root_context(document.clone() as std::rc::Rc<dyn Document>);
+            virtual_dom
+                .provide_root_context(server_context.get::<QueryCache>().unwrap_or_default());
+            virtual_dom.provide_root_context(server_context.get::<Variants>().unwrap_or_default());
+            if let Some(theme) = server_context.theme() {
+                virtual_dom.provide_root_context(crate::theme::Theme(theme));
+            }
 
             // poll the future, which may call server_context()
             tracing::info!("Rebuilding vdom");
             with_server_context(server_context.clone(), || virtual_dom.rebuild_in_place());
 
+            if strip_hydration {
+                // Without javascript, streamed suspense boundaries can't be swapped into the page. Resolve everything and send the page in order instead
+                while virtual_dom.suspended_tasks_remaining() {
+                    ProvideServerContext::new(
+                        virtual_dom.wait_for_suspense_work(),
+                        server_context.clone(),
+                    )
+                    .await;
+                    ProvideServerContext::new(
+                        virtual_dom.render_suspense_immediate(),
+                        server_context.clone(),
+                    )
+                    .await;
+                }
+
+                let mut html = String::new();
+                let result = wrapper.render_head(&mut html, &virtual_dom).and_then(|_| {
+                    renderer.pre_render = false;
+                    renderer.reset_hydration();
+                    let body_start = html.len();
+                    let rendered = renderer.render_to(&mut html, &virtual_dom);
+                    renderer.pre_render = true;
+                    rendered.map_err(dioxus_isrg::IncrementalRendererError::RenderError)?;
+                    if let Some(placeholder) =
+                        wrapper.check_empty_render(&route, &html[body_start..])?
+                    {
+                        return Ok(placeholder);
+                    }
+                    wrapper.render_after_main(&mut html, &virtual_dom)?;
+                    wrapper.render_after_body(&mut html)?;
+                    Ok(wrapper.post_process_page(html))
+                });
+                _ = into.start_send(result);
+
+                myself.renderers.write().unwrap().push(renderer);
+                return;
+            }
+
             let mut pre_body = String::new();
 
             if let Err(err) = wrapper.render_head(&mut pre_body, &virtual_dom) {
@@ -185,7 +272,13 @@ impl SsrRendererPool {
                 return;
             }
 
-            let stream = Arc::new(StreamingRenderer::new(pre_body, into));
+            // If an empty render replaces the page, hold the head back until we know if the app rendered anything
+            let (head, held_head) = if wrapper.cfg.empty_render.replaces_page() {
+                (String::new(), Some(pre_body))
+            } else {
+                (pre_body, None)
+            };
+            let stream = Arc::new(StreamingRenderer::new(head, into));
             let scope_to_mount_mapping = Arc::new(RwLock::new(HashMap::new()));
 
             renderer.pre_render = true;
@@ -261,12 +354,33 @@ impl SsrRendererPool {
             }
 
             // Render the initial frame with loading placeholders
-            let mut initial_frame = renderer.render(&virtual_dom);
+            let mut initial_frame = wrapper.post_process(renderer.render(&virtual_dom));
+            // Without pending suspense, the initial frame is the whole page
+            if !virtual_dom.suspended_tasks_remaining() {
+                match wrapper.check_empty_render(&route, &initial_frame) {
+                    Ok(Some(placeholder)) => {
+                        stream.render(placeholder);
+                        renderer.reset_render_components();
+                        myself.renderers.write().unwrap().push(renderer);
+                        return;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        throw_error!(err);
+                    }
+                }
+            }
+            if let Some(marker) = wrapper.hydration_check_marker(&initial_frame, None) {
+                initial_frame.push_str(&marker);
+            }
 
             // Along with the initial frame, we render the html after the main element, but before the body tag closes. This should include the script that starts loading the wasm bundle.
             if let Err(err) = wrapper.render_after_main(&mut initial_frame, &virtual_dom) {
                 throw_error!(err);
             }
+            if let Some(head) = held_head {
+                initial_frame.insert_str(0, &head);
+            }
             stream.render(initial_frame);
 
             // After the initial render, we need to resolve suspense
@@ -291,22 +405,32 @@ impl SsrRendererPool {
                     // If the suspense boundary was immediately removed, it may not have a mount. We can just skip resolving it
                     if let Some(pending_suspense_boundary) = pending_suspense_boundary {
                         let mut resolved_chunk = String::new();
+                        let mut resolved_html = None;
                         // After we replace the placeholder in the dom with javascript, we need to send down the resolved data so that the client can hydrate the node
                         let render_suspense = |into: &mut String| {
                             renderer.reset_hydration();
-                            renderer.render_scope(into, &virtual_dom, scope)
+                            let start = into.len();
+                            renderer.render_scope(into, &virtual_dom, scope)?;
+                            resolved_html = Some(into[start..].to_string());
+                            Ok(())
                         };
                         let resolved_data = serialize_server_data(&virtual_dom, scope);
+                        let mount = pending_suspense_boundary.mount;
                         if let Err(err) = stream.replace_placeholder(
-                            pending_suspense_boundary.mount,
+                            mount.clone(),
                             render_suspense,
                             resolved_data,
                             &mut resolved_chunk,
                         ) {
                             throw_error!(dioxus_isrg::IncrementalRendererError::RenderError(err));
                         }
+                        if let Some(marker) = resolved_html
+                            .and_then(|html| wrapper.hydration_check_marker(&html, Some(&mount)))
+                        {
+                            resolved_chunk.push_str(&marker);
+                        }
 
-                        stream.render(resolved_chunk);
+                        stream.render(wrapper.post_process(resolved_chunk));
                         // Freeze the suspense boundary to prevent future reruns of any child nodes of the suspense boundary
                         if let Some(suspense) =
                             SuspenseContext::downcast_suspense_boundary_from_scope(
@@ -335,22 +459,29 @@ impl SsrRendererPool {
             }
 
             // If incremental rendering is enabled, add the new render to the cache without the streaming bits
-            if let Some(incremental) = &self.incremental_cache {
+            if let Some(incremental) = self.incremental_cache.as_ref().filter(|_| !bypass_cache) {
                 let mut cached_render = String::new();
                 if let Err(err) = wrapper.render_head(&mut cached_render, &virtual_dom) {
                     throw_error!(err);
                 }
                 renderer.reset_hydration();
+                let body_start = cached_render.len();
                 if let Err(err) = renderer.render_to(&mut cached_render, &virtual_dom) {
                     throw_error!(dioxus_isrg::IncrementalRendererError::RenderError(err));
                 }
+                if let Some(marker) =
+                    wrapper.hydration_check_marker(&cached_render[body_start..], None)
+                {
+                    cached_render.push_str(&marker);
+                }
                 if let Err(err) = wrapper.render_after_main(&mut cached_render, &virtual_dom) {
                     throw_error!(err);
                 }
                 cached_render.push_str(&post_streaming);
+                let cached_render = wrapper.post_process_page(cached_render);
 
                 if let Ok(mut incremental) = incremental.write() {
-                    let _ = incremental.cache(route, cached_render);
+                    let _ = incremental.cache_with_preloads(route, cached_render, preloads);
                 }
             }
 
@@ -358,7 +489,8 @@ impl SsrRendererPool {
 
             renderer.reset_render_components();
             myself.renderers.write().unwrap().push(renderer);
-        });
+        };
+        let join_handle = spawn_platform(move || with_collector(collector, render()));
 
         Ok((
             RenderFreshness::now(None),
@@ -370,6 +502,15 @@ impl SsrRendererPool {
     }
 }
 
+/// Run a render with the log collector of the request if it captures its logs
+async fn with_collector(collector: Option<tracing::Dispatch>, render: impl Future<Output = ()>) {
+    use tracing::instrument::WithSubscriber;
+    match collector {
+        Some(collector) => render.with_subscriber(collector).await,
+        None => render.await,
+    }
+}
+
 /// Start capturing errors at a suspense boundary. If the parent suspense boundary is frozen, we need to capture the errors in the suspense boundary
 /// and send them to the client to continue bubbling up
 fn start_capturing_errors(suspense_scope: ScopeId) {
@@ -387,6 +528,15 @@ fn serialize_server_data(virtual_dom: &VirtualDom, scope: ScopeId) -> String {
     html_data.serialized()
 }
 
+/// Marks renders that were served from the incremental cache in the server context
+#[derive(Clone, Copy)]
+pub(crate) struct CacheHit;
+
+/// The values of the `Link` preload headers of a render. They are stored with the render in the incremental cache, so cache hits
+/// send the same headers as the render they were cached from
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PreloadLinks(pub(crate) Vec<String>);
+
 /// State used in server side rendering. This utilizes a pool of [`dioxus_ssr::Renderer`]s to cache static templates between renders.
 #[derive(Clone)]
 pub struct SSRState {
@@ -402,6 +552,37 @@ impl SSRState {
         }
     }
 
+    /// Get a page of the routes in the memory cache that start with `prefix` sorted by route, and the number of matching routes.
+    /// Returns `None` if incremental rendering is disabled.
+    #[cfg(feature = "axum")]
+    pub(crate) fn incremental_cache_page(
+        &self,
+        prefix: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Option<(usize, Vec<CachedRouteSummary>)> {
+        let cache = self.renderers.incremental_cache.as_ref()?;
+        // Only copy out the requested page while the cache is locked
+        let cache = cache.read().ok()?;
+        let mut entries: Vec<_> = cache
+            .memory_cache_entries()
+            .filter(|entry| entry.route.starts_with(prefix))
+            .collect();
+        entries.sort_by(|a, b| a.route.cmp(b.route));
+        let total = entries.len();
+        let page = entries
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|entry| CachedRouteSummary {
+                route: entry.route.to_string(),
+                created_at: entry.created_at.to_rfc3339(),
+                size: entry.size,
+            })
+            .collect();
+        Some((total, page))
+    }
+
     /// Render the application to HTML.
     pub async fn render<'a>(
         &'a self,
@@ -416,22 +597,49 @@ impl SSRState {
         ),
         dioxus_isrg::IncrementalRendererError,
     > {
-        self.renderers
+        let (freshness, stream) = self
+            .renderers
             .clone()
             .render_to(cfg, route, virtual_dom_factory, server_context)
-            .await
+            .await?;
+
+        // The nonce is added to every chunk after rendering, so cached pages get the nonce of the current request
+        let nonce = server_context.nonce();
+        let stream = stream.map(move |chunk| match &nonce {
+            Some(nonce) => chunk.map(|html| add_nonce(&html, nonce)),
+            None => chunk,
+        });
+
+        Ok((freshness, stream))
     }
 }
 
+/// A route in the incremental cache as it is shown by the debug handler
+#[cfg(feature = "axum")]
+#[derive(serde::Serialize)]
+pub(crate) struct CachedRouteSummary {
+    route: String,
+    created_at: String,
+    size: usize,
+}
+
 /// The template that wraps the body of the HTML for a fullstack page. This template contains the data needed to hydrate server functions that were run on the server.
 pub struct FullstackHTMLTemplate {
     cfg: ServeConfig,
+    strip_hydration: bool,
+    locale: Option<String>,
+    theme: Option<String>,
 }
 
 impl FullstackHTMLTemplate {
     /// Create a new [`FullstackHTMLTemplate`].
     pub fn new(cfg: &ServeConfig) -> Self {
-        Self { cfg: cfg.clone() }
+        Self {
+            cfg: cfg.clone(),
+            strip_hydration: false,
+            locale: None,
+            theme: None,
+        }
     }
 }
 
@@ -451,7 +659,7 @@ impl FullstackHTMLTemplate {
             document.and_then(|document| document.title())
         };
 
-        to.write_str(&index.head_before_title)?;
+        self.render_before_title(to)?;
         if let Some(title) = title {
             to.write_str(&title)?;
         } else {
@@ -459,6 +667,9 @@ impl FullstackHTMLTemplate {
         }
         to.write_str(&index.head_after_title)?;
 
+        self.render_styles(to)?;
+        self.render_module_preloads(to)?;
+
         let document: Option<std::rc::Rc<ServerDocument>> =
             virtual_dom.in_runtime(|| ScopeId::ROOT.consume_context());
         if let Some(document) = document {
@@ -474,6 +685,112 @@ impl FullstackHTMLTemplate {
         Ok(())
     }
 
+    /// Render the start of the head and set the attributes of the `<html>` element from the locale and theme of the request
+    fn render_before_title<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        let ServeConfig { index, .. } = &self.cfg;
+
+        let Some((start, html_tag)) =
+            crate::serve_config::find_opening_tag(&index.head_before_title, "html")
+        else {
+            to.write_str(&index.head_before_title)?;
+            return Ok(());
+        };
+        // Our attributes go right after the tag name so they take precedence over the attributes of the template
+        let (before, after) = index
+            .head_before_title
+            .split_at(start + "<".len() + html_tag.name().len());
+
+        let mut attributes = String::new();
+        if let Some(locale) = &self.locale {
+            let dir = if self.cfg.is_rtl(locale) {
+                "rtl"
+            } else {
+                "ltr"
+            };
+            attributes.push_str(&format!(
+                r#" lang="{}" dir="{dir}""#,
+                escape_attribute(locale)
+            ));
+        } else if self.cfg.charset_and_lang && html_tag.attribute("lang").is_none() {
+            attributes.push_str(r#" lang="en""#);
+        }
+        if let Some(theme) = &self.theme {
+            let theme = escape_attribute(theme);
+            attributes.push_str(&format!(r#" {THEME_ATTRIBUTE}="{theme}""#));
+            // A second class attribute would be ignored, so leave the classes of the template alone
+            if html_tag.attribute("class").is_none() {
+                attributes.push_str(&format!(r#" class="{theme}""#));
+            }
+        }
+        write!(to, "{before}{attributes}{after}")?;
+
+        Ok(())
+    }
+
+    /// Render the critical styles inline and preload the deferred stylesheets
+    fn render_styles<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        let ServeConfig {
+            critical_styles,
+            deferred_stylesheets,
+            ..
+        } = &self.cfg;
+
+        for css in critical_styles {
+            write!(to, "<style>{css}</style>")?;
+        }
+        for href in deferred_stylesheets {
+            let href = escape_attribute(href);
+            write!(
+                to,
+                r#"<link rel="preload" as="style" href="{href}" onload="this.onload=null;this.rel='stylesheet'"><noscript><link rel="stylesheet" href="{href}"></noscript>"#
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// The values of the `Link` headers that let the browser start fetching the hydration modules before it parses the page
+    fn preload_links(&self) -> Vec<String> {
+        // Pages without hydration don't load any modules
+        if self.strip_hydration || self.cfg.module_preloads.is_empty() {
+            return Vec::new();
+        }
+        let link = self
+            .cfg
+            .module_preloads
+            .iter()
+            .map(|url| format!("<{url}>; rel=modulepreload"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        vec![link]
+    }
+
+    /// Preload the modules the client needs to hydrate
+    fn render_module_preloads<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        // Pages without hydration don't load any modules
+        if self.strip_hydration {
+            return Ok(());
+        }
+        for href in &self.cfg.module_preloads {
+            write!(
+                to,
+                r#"<link rel="modulepreload" href="{}">"#,
+                escape_attribute(href)
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Render any content before the body of the page.
     fn render_before_body<R: std::fmt::Write>(
         &self,
@@ -483,7 +800,9 @@ impl FullstackHTMLTemplate {
 
         to.write_str(&index.close_head)?;
 
-        write!(to, "<script>{INITIALIZE_STREAMING_JS}</script>")?;
+        if !self.strip_hydration {
+            write!(to, "<script>{INITIALIZE_STREAMING_JS}</script>")?;
+        }
 
         Ok(())
     }
@@ -496,6 +815,11 @@ impl FullstackHTMLTemplate {
     ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
         let ServeConfig { index, .. } = &self.cfg;
 
+        if self.strip_hydration {
+            to.write_str(&index.post_main)?;
+            return Ok(());
+        }
+
         // Collect the initial server data from the root node. For most apps, no use_server_futures will be resolved initially, so this will be full on `None`s.
         // Sending down those Nones are still important to tell the client not to run the use_server_futures that are already running on the backend
         let resolved_data = serialize_server_data(virtual_dom, ScopeId::ROOT);
@@ -503,6 +827,30 @@ impl FullstackHTMLTemplate {
             to,
             r#"<script>window.initial_dioxus_hydration_data="{resolved_data}";</script>"#,
         )?;
+
+        // Send down any queries that were cached during the render so the client doesn't need to fetch them again
+        let query_cache: Option<QueryCache> =
+            virtual_dom.in_runtime(|| ScopeId::ROOT.consume_context());
+        if let Some(query_cache) = query_cache
+            .filter(|cache| !cache.is_empty())
+            .and_then(|cache| cache.serialized())
+        {
+            write!(
+                to,
+                r#"<script type="application/octet-stream" id="{QUERY_CACHE_ID}">{query_cache}</script>"#,
+            )?;
+        }
+        // Send down the variants of the request so the client hydrates the same components
+        let variants: Option<Variants> = virtual_dom.in_runtime(|| ScopeId::ROOT.consume_context());
+        if let Some(variants) = variants
+            .filter(|variants| !variants.is_empty())
+            .and_then(|variants| variants.serialized())
+        {
+            write!(
+                to,
+                r#"<script type="application/octet-stream" id="{VARIANTS_ID}">{variants}</script>"#,
+            )?;
+        }
         to.write_str(&index.post_main)?;
 
         Ok(())
@@ -528,7 +876,11 @@ impl FullstackHTMLTemplate {
         body: impl std::fmt::Display,
     ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
         self.render_head(to, virtual_dom)?;
-        write!(to, "{body}")?;
+        let body = body.to_string();
+        to.write_str(&body)?;
+        if let Some(marker) = self.hydration_check_marker(&body, None) {
+            to.write_str(&marker)?;
+        }
         self.render_after_main(to, virtual_dom)?;
         self.render_after_body(to)?;
 
@@ -536,8 +888,245 @@ impl FullstackHTMLTemplate {
     }
 }
 
+impl FullstackHTMLTemplate {
+    /// Create the element that holds the hydration checksum of some rendered html. If `boundary` is `None`, the html is the body of the
+    /// document. Returns `None` if the check is disabled for that granularity.
+    fn hydration_check_marker(&self, html: &str, boundary: Option<&Mount>) -> Option<String> {
+        let ServeConfig {
+            hydration_check,
+            hydration_mismatch_policy,
+            ..
+        } = &self.cfg;
+
+        if self.strip_hydration {
+            return None;
+        }
+
+        let target = match (hydration_check, boundary) {
+            (HydrationCheck::Off, _) | (HydrationCheck::Document, Some(_)) => return None,
+            (_, None) => r#"id="dioxus-hydration-check""#.to_string(),
+            (HydrationCheck::SuspenseBoundary, Some(mount)) => {
+                format!(r#"data-dioxus-hydration-check="{mount}""#)
+            }
+        };
+
+        Some(format!(
+            r#"<script type="application/json" {target}>{{"checksum":{},"policy":"{}"}}</script>"#,
+            structure_checksum(html),
+            hydration_mismatch_policy.as_str()
+        ))
+    }
+}
+
+impl FullstackHTMLTemplate {
+    /// Apply the [`EmptyRenderPolicy`] if the html of the app has no content. Returns the page to send instead of the render if there is one
+    fn check_empty_render(
+        &self,
+        route: &str,
+        html: &str,
+    ) -> Result<Option<String>, dioxus_isrg::IncrementalRendererError> {
+        if !is_empty_render(html) {
+            return Ok(None);
+        }
+        match &self.cfg.empty_render {
+            EmptyRenderPolicy::Allow => Ok(None),
+            EmptyRenderPolicy::Warn => {
+                tracing::warn!("The app rendered no content for \"{route}\"");
+                Ok(None)
+            }
+            EmptyRenderPolicy::Error => Err(dioxus_isrg::IncrementalRendererError::Other(
+                format!("The app rendered no content for \"{route}\"").into(),
+            )),
+            EmptyRenderPolicy::Placeholder(placeholder) => Ok(Some(placeholder.clone())),
+        }
+    }
+
+    /// Apply the enabled transforms like lazy images to a chunk of html
+    fn post_process(&self, mut html: String) -> String {
+        if self.cfg.auto_lazy_images {
+            html = lazy_images(&html);
+        }
+        html
+    }
+
+    /// Apply the enabled transforms to a whole page. Pretty html is only applied here because the indentation of a streamed chunk
+    /// depends on the elements that are still open from the chunks before it
+    fn post_process_page(&self, html: String) -> String {
+        let html = self.post_process(html);
+        if self.cfg.pretty_html {
+            return pretty_html(&html);
+        }
+        html
+    }
+}
+
+/// Add line breaks and indentation between the tags of some html. Text and the contents of raw text elements are left untouched.
+pub(crate) fn pretty_html(html: &str) -> String {
+    const VOID_ELEMENTS: &[&str] = &[
+        "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+        "track", "wbr",
+    ];
+
+    let mut pretty = String::with_capacity(html.len() * 2);
+    let mut depth = 0usize;
+    // Only break lines directly between two element tags. Breaking around comments would change the text nodes hydration expects
+    let mut after_element_tag = false;
+    // Empty elements stay on one line so they don't gain a text node
+    let mut after_opening_tag = false;
+    // Whitespace inside of `<pre>` elements is significant, so their contents are copied verbatim
+    let mut pre_depth = 0usize;
+    for token in Tokens::new(html) {
+        let tag = match token {
+            Token::Tag(tag) => tag,
+            Token::Text(text) => {
+                pretty.push_str(text);
+                after_element_tag = false;
+                continue;
+            }
+            Token::Comment(comment) => {
+                pretty.push_str(comment);
+                after_element_tag = false;
+                after_opening_tag = false;
+                continue;
+            }
+        };
+
+        if pre_depth > 0 {
+            if tag.is("pre") {
+                if tag.is_closing() {
+                    pre_depth -= 1;
+                } else if !tag.is_self_closing() {
+                    pre_depth += 1;
+                }
+            }
+            if pre_depth > 0 {
+                pretty.push_str(tag.source());
+                continue;
+            }
+            after_element_tag = false;
+        }
+
+        let closing = tag.is_closing();
+        if closing {
+            depth = depth.saturating_sub(1);
+        }
+        let empty_element = closing && after_opening_tag;
+        if after_element_tag && !empty_element {
+            pretty.push('\n');
+            for _ in 0..depth {
+                pretty.push_str("  ");
+            }
+        }
+        pretty.push_str(tag.source());
+        after_element_tag = true;
+        after_opening_tag = false;
+
+        let opens_element =
+            !closing && !tag.is_self_closing() && !VOID_ELEMENTS.iter().any(|name| tag.is(name));
+        if opens_element {
+            depth += 1;
+            after_opening_tag = true;
+            if tag.is("pre") {
+                pre_depth = 1;
+            }
+        }
+    }
+    pretty
+}
+
+/// Add `loading="lazy"` and `decoding="async"` to the images in some html that don't set them. Images with a `data-priority`
+/// attribute are critical and left untouched.
+fn lazy_images(html: &str) -> String {
+    let mut lazy = String::with_capacity(html.len());
+    for token in Tokens::new(html) {
+        match token {
+            Token::Tag(tag)
+                if tag.is("img")
+                    && !tag.is_closing()
+                    && tag.attribute("data-priority").is_none() =>
+            {
+                let mut attributes = String::new();
+                if tag.attribute("loading").is_none() {
+                    attributes.push_str(r#" loading="lazy""#);
+                }
+                if tag.attribute("decoding").is_none() {
+                    attributes.push_str(r#" decoding="async""#);
+                }
+                lazy.push_str(&tag.with_attributes(&attributes));
+            }
+            token => lazy.push_str(token.source()),
+        }
+    }
+    lazy
+}
+
+/// Add a `nonce` attribute to every `<script>` and `<style>` element in some html that doesn't have one
+fn add_nonce(html: &str, nonce: &str) -> String {
+    let nonce_attribute = format!(r#" nonce="{}""#, escape_attribute(nonce));
+    let mut with_nonce = String::with_capacity(html.len());
+    for token in Tokens::new(html) {
+        match token {
+            Token::Tag(tag)
+                if (tag.is("script") || tag.is("style"))
+                    && !tag.is_closing()
+                    && tag.attribute("nonce").is_none() =>
+            {
+                with_nonce.push_str(&tag.with_attributes(&nonce_attribute));
+            }
+            token => with_nonce.push_str(token.source()),
+        }
+    }
+    with_nonce
+}
+
+/// Check if some html has no content other than whitespace and comments
+fn is_empty_render(html: &str) -> bool {
+    Tokens::new(html).all(|token| match token {
+        Token::Text(text) => text.trim().is_empty(),
+        Token::Comment(_) => true,
+        Token::Tag(_) => false,
+    })
+}
+
+/// Hash the structure of some html. See [`HydrationCheck`] for a description of the checksum.
+fn structure_checksum(html: &str) -> u32 {
+    const FNV_OFFSET_BASIS: u32 = 0x811c9dc5;
+    const FNV_PRIME: u32 = 0x01000193;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for token in Tokens::new(html) {
+        let Token::Tag(tag) = token else {
+            continue;
+        };
+        if tag.is_closing() {
+            continue;
+        }
+        for byte in tag.name().bytes().chain(std::iter::once(b',')) {
+            hash ^= byte.to_ascii_lowercase() as u32;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
 fn pre_renderer() -> Renderer {
     let mut renderer = Renderer::default();
     renderer.pre_render = true;
     renderer
 }
+
+#[test]
+fn default_builder_marks_rtl_locales() {
+    let cfg = ServeConfigBuilder::default()
+        .index_html(
+            r#"<!DOCTYPE html><html><head><title>App</title></head><body><div id="main"></div></body></html>"#
+                .to_string(),
+        )
+        .build()
+        .unwrap();
+    let mut template = FullstackHTMLTemplate::new(&cfg);
+    template.locale = Some("ar-EG".to_string());
+    let mut html = String::new();
+    template.render_before_title(&mut html).unwrap();
+    assert!(html.contains(r#"<html lang="ar-EG" dir="rtl">"#));
+}
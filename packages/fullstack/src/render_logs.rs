@@ -0,0 +1,94 @@
+//! Capture the logs components write while a page renders. See [`ServeConfigBuilder::capture_render_logs`](crate::prelude::ServeConfigBuilder::capture_render_logs)
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The log lines that were written while a page rendered. The logs are added to the extensions of the response of the page.
+///
+/// When the page is streamed, lines are added until the last suspense boundary resolves, so read the lines after the body finishes
+/// to see every line.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # fn app() -> Element { todo!() }
+/// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_on_response(
+///     |_, response, _| {
+///         if let Some(logs) = response.extensions().get::<RenderLogs>() {
+///             for line in logs.lines() {
+///                 println!("{line}");
+///             }
+///         }
+///     },
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RenderLogs {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl RenderLogs {
+    /// Get the lines that were logged so far
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().clone()
+    }
+
+    /// Create a subscriber that writes the events of one render at `level` or more severe into these logs
+    pub(crate) fn collector(&self, level: tracing::Level) -> tracing::Dispatch {
+        tracing::Dispatch::new(Collector {
+            logs: self.clone(),
+            level,
+            next_span: AtomicU64::new(1),
+        })
+    }
+}
+
+/// A subscriber that only records events. It is installed for the task of a single render, so concurrent renders never share lines
+struct Collector {
+    logs: RenderLogs,
+    level: tracing::Level,
+    next_span: AtomicU64,
+}
+
+impl tracing::Subscriber for Collector {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        *metadata.level() <= self.level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(self.level.into())
+    }
+
+    fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {}:", metadata.level(), metadata.target());
+        event.record(&mut LineVisitor(&mut line));
+        self.logs.lines.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, _: &tracing::span::Id) {}
+
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+/// Writes the fields of an event after the level and target of a line
+struct LineVisitor<'a>(&'a mut String);
+
+impl tracing::field::Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            _ = write!(self.0, " {value:?}");
+        } else {
+            _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Values that must never be sent to the client in the hydration data.
+
+use std::cell::Cell;
+
+std::thread_local! {
+    /// Set while the server serializes hydration data. Secrets that are serialized while this is set mark the data as leaked
+    static HYDRATION_SERIALIZATION: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// A value like an API key that is only used on the server. If a secret ends up in the data of [`use_server_future`](crate::prelude::use_server_future)
+/// or [`use_server_cached`](crate::prelude::use_server_cached) that is sent to the client for hydration, the render panics in debug builds.
+/// Release builds log an error and drop the data that contains the secret instead of sending it.
+///
+/// Outside of the hydration data, the secret serializes like the value inside, so it can still be sent to other services.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// struct Weather {
+///     temperature: f32,
+///     // This would panic in debug builds if the weather was read with `use_server_future`
+///     // api_key: Secret<String>,
+/// }
+///
+/// fn api_key() -> Secret<String> {
+///     Secret::new(std::env::var("WEATHER_API_KEY").unwrap_or_default())
+/// }
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wrap a value that must not be sent to the client
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Get the value inside the secret
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Take the value out of the secret
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Secret<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let in_hydration_data = HYDRATION_SERIALIZATION.with(|leaked| match leaked.get() {
+            Some(_) => {
+                leaked.set(Some(true));
+                true
+            }
+            None => false,
+        });
+        if in_hydration_data {
+            serializer.serialize_unit()
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Secret<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Serialize hydration data and check if a [`Secret`] was part of it
+pub(crate) fn serialize_hydration_data(serialize: impl FnOnce()) -> bool {
+    let outer = HYDRATION_SERIALIZATION.with(|leaked| leaked.replace(Some(false)));
+    serialize();
+    HYDRATION_SERIALIZATION
+        .with(|leaked| leaked.replace(outer))
+        .unwrap_or_default()
+}
@@ -2,25 +2,105 @@
 #![allow(non_snake_case)]
 
 use std::fs::File;
+use std::future::Future;
 use std::io::Read;
 use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
 
 use dioxus_lib::prelude::dioxus_core::LaunchConfig;
+use dioxus_ssr::tags::{Tag, Token, Tokens};
 
+use crate::prelude::{DioxusServerContext, QueryCache, Variants};
 use crate::server::ContextProviders;
+use crate::{OsRandSource, RandSource, SystemTimeSource, TimeSource};
 
 /// A ServeConfig is used to configure how to serve a Dioxus application. It contains information about how to serve static assets, and what content to render with [`dioxus-ssr`].
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct ServeConfigBuilder {
     pub(crate) root_id: Option<&'static str>,
     pub(crate) index_html: Option<String>,
     pub(crate) index_path: Option<PathBuf>,
     pub(crate) incremental: Option<dioxus_isrg::IncrementalRendererConfig>,
     pub(crate) context_providers: ContextProviders,
+    pub(crate) route_pattern: Option<RoutePatternFn>,
+    pub(crate) span_name: Option<SpanNameFn>,
+    pub(crate) prefetch_queries: Option<PrefetchQueriesFn>,
+    pub(crate) rand_source: Option<Arc<dyn RandSource>>,
+    pub(crate) critical_styles: Vec<String>,
+    pub(crate) deferred_stylesheets: Vec<String>,
+    pub(crate) hydration_check: HydrationCheck,
+    pub(crate) hydration_mismatch_policy: HydrationMismatchPolicy,
+    pub(crate) crawler_user_agents: Vec<String>,
+    pub(crate) pretty_html: bool,
+    pub(crate) auto_lazy_images: bool,
+    pub(crate) locales: Vec<String>,
+    pub(crate) rtl_languages: Vec<String>,
+    pub(crate) authenticated: Option<AuthenticatedFn>,
+    pub(crate) module_manifest: Option<ModuleManifest>,
+    pub(crate) content_security_policy: Option<String>,
+    pub(crate) csp_nonce: bool,
+    pub(crate) empty_render: EmptyRenderPolicy,
+    pub(crate) variants: Option<VariantsFn>,
+    pub(crate) theme_cookie: Option<ThemeCookie>,
+    pub(crate) on_shutdown: Option<OnShutdownFn>,
+    pub(crate) cache_bypass_header: bool,
+    pub(crate) stream_timeouts: StreamTimeouts,
+    pub(crate) permissions_policy: Option<PermissionsPolicy>,
+    pub(crate) capture_render_logs: Option<tracing::Level>,
+    pub(crate) robots: Option<String>,
+    pub(crate) static_page_prefixes: Vec<String>,
+    pub(crate) charset_and_lang: bool,
+    pub(crate) handler: HandlerConfig,
+}
+
+/// The header that lets a request skip the incremental cache. See [`ServeConfigBuilder::cache_bypass_header`]
+const CACHE_BYPASS_HEADER: &str = "x-dioxus-cache";
+
+/// Languages that are written right to left by default
+const DEFAULT_RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "ha", "he", "khw", "ks", "ku", "ps", "sd", "ur", "yi",
+];
+
+/// A function that resolves the route pattern for a concrete path
+pub(crate) type RoutePatternFn = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// A function that names the tracing span of a render from the request and its route pattern
+pub(crate) type SpanNameFn =
+    Arc<dyn Fn(&http::request::Parts, Option<&str>) -> String + Send + Sync>;
+
+/// A function that checks if a request is from an authenticated user
+pub(crate) type AuthenticatedFn = Arc<dyn Fn(&http::request::Parts) -> bool + Send + Sync>;
+
+/// The cookie the theme of a request is read from and the theme to use without the cookie
+#[derive(Clone, Debug)]
+pub(crate) struct ThemeCookie {
+    name: String,
+    default: String,
 }
 
+/// A function that assigns the component variants for a request
+pub(crate) type VariantsFn = Arc<dyn Fn(&http::request::Parts) -> Variants + Send + Sync>;
+
+/// A function that runs when the server shuts down
+pub(crate) type OnShutdownFn =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// A function that fills the query cache for a request before it is rendered
+pub(crate) type PrefetchQueriesFn = Arc<
+    dyn Fn(DioxusServerContext, QueryCache) -> Pin<Box<dyn Future<Output = ()> + Send>>
+        + Send
+        + Sync,
+>;
+
 impl LaunchConfig for ServeConfigBuilder {}
 
+impl Default for ServeConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ServeConfigBuilder {
     /// Create a new ServeConfigBuilder with incremental static generation disabled and the default index.html settings
     pub fn new() -> Self {
@@ -30,6 +110,38 @@ impl ServeConfigBuilder {
             index_path: None,
             incremental: None,
             context_providers: Default::default(),
+            route_pattern: None,
+            span_name: None,
+            prefetch_queries: None,
+            rand_source: None,
+            critical_styles: Vec::new(),
+            deferred_stylesheets: Vec::new(),
+            hydration_check: HydrationCheck::Off,
+            hydration_mismatch_policy: HydrationMismatchPolicy::Warn,
+            crawler_user_agents: Vec::new(),
+            pretty_html: false,
+            auto_lazy_images: false,
+            locales: Vec::new(),
+            rtl_languages: DEFAULT_RTL_LANGUAGES
+                .iter()
+                .map(|language| language.to_string())
+                .collect(),
+            authenticated: None,
+            module_manifest: None,
+            content_security_policy: None,
+            csp_nonce: false,
+            empty_render: EmptyRenderPolicy::Allow,
+            variants: None,
+            theme_cookie: None,
+            on_shutdown: None,
+            cache_bypass_header: false,
+            stream_timeouts: StreamTimeouts::default(),
+            permissions_policy: None,
+            capture_render_logs: None,
+            robots: None,
+            static_page_prefixes: Vec::new(),
+            charset_and_lang: true,
+            handler: HandlerConfig::default(),
         }
     }
 
@@ -86,70 +198,945 @@ impl ServeConfigBuilder {
     /// </html>
     /// ```
     ///
-    /// You can set the root id to `"my-custom-root"` to render the app into that element:
+    /// You can set the root id to `"my-custom-root"` to render the app into that element:
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().root_id("app"));
+    /// }
+    ///
+    /// // You also need to set the root id in your web config
+    /// web! {
+    ///     cfg = cfg.with_web_config(dioxus::web::Config::default().rootname("app"));
+    /// }
+    ///
+    /// // And desktop config
+    /// desktop! {
+    ///     cfg = cfg.with_desktop_config(dioxus::desktop::Config::default().with_root_name("app"));
+    /// }
+    ///
+    /// // Finally, launch the app with the config
+    /// LaunchBuilder::new()
+    ///     .with_cfg(cfg)
+    ///     .launch(app);
+    /// ```
+    pub fn root_id(mut self, root_id: &'static str) -> Self {
+        self.root_id = Some(root_id);
+        self
+    }
+
+    /// Provide context to the root and server functions. You can use this context
+    /// while rendering with [`consume_context`](dioxus_lib::prelude::consume_context) or in server functions with [`FromContext`](crate::prelude::FromContext).
+    ///
+    /// Context will be forwarded from the LaunchBuilder if it is provided.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// dioxus::LaunchBuilder::new()
+    ///     // You can provide context to your whole app (including server functions) with the `with_context` method on the launch builder
+    ///     .with_context(server_only! {
+    ///         1234567890u32
+    ///     })
+    ///     .launch(app);
+    ///
+    /// #[server]
+    /// async fn read_context() -> Result<u32, ServerFnError> {
+    ///     // You can extract values from the server context with the `extract` function
+    ///     let FromContext(value) = extract().await?;
+    ///     Ok(value)
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     let future = use_resource(read_context);
+    ///     rsx! {
+    ///         h1 { "{future:?}" }
+    ///     }
+    /// }
+    /// ```
+    pub fn context_providers(mut self, state: ContextProviders) -> Self {
+        self.context_providers = state;
+        self
+    }
+
+    /// Set a function that resolves the route pattern (for example `/products/:id`) for the concrete path of a request (for example `/products/42`).
+    /// The pattern is available while rendering with [`DioxusServerContext::route_pattern`](crate::prelude::DioxusServerContext::route_pattern).
+    ///
+    /// If you are using the router, you can pass [`Routable::route_pattern`](https://docs.rs/dioxus-router/latest/dioxus_router/routable/trait.Routable.html#method.route_pattern)
+    /// for your route enum directly (`.route_pattern(Route::route_pattern)`).
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().route_pattern(|path| {
+    ///         path.starts_with("/products/").then(|| "/products/:id".to_string())
+    ///     }));
+    /// }
+    /// ```
+    pub fn route_pattern(
+        mut self,
+        resolve: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.route_pattern = Some(Arc::new(resolve));
+        self
+    }
+
+    /// Set a function that names the tracing span of each render. The function receives the request and the route pattern from
+    /// [`Self::route_pattern`] if one matched. By default, spans are named after the route pattern, or the path if no pattern matched.
+    ///
+    /// Span names in `tracing` are static, so the name is recorded in the `otel.name` field which OpenTelemetry exporters use as the
+    /// name of the span. The route pattern is also recorded in the `route` field.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().span_name(|parts, pattern| {
+    ///         format!("{} {}", parts.method, pattern.unwrap_or("unmatched"))
+    ///     }));
+    /// }
+    /// ```
+    pub fn span_name(
+        mut self,
+        name: impl Fn(&http::request::Parts, Option<&str>) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.span_name = Some(Arc::new(name));
+        self
+    }
+
+    /// Prefetch queries for a request before it is rendered. The callback receives the server context for the request and the
+    /// [`QueryCache`] that is provided to the app while rendering. Any results inserted into the cache are serialized into
+    /// the page so the client can hydrate without fetching them again.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().prefetch_queries(|server_context, cache| async move {
+    ///         if server_context.request_parts().uri.path() == "/profile" {
+    ///             cache.insert("user", &"Ferris".to_string());
+    ///         }
+    ///     }));
+    /// }
+    /// ```
+    pub fn prefetch_queries<F, Fut>(mut self, prefetch: F) -> Self
+    where
+        F: Fn(DioxusServerContext, QueryCache) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.prefetch_queries = Some(Arc::new(move |server_context, cache| {
+            Box::pin(prefetch(server_context, cache)) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }));
+        self
+    }
+
+    /// Run a callback after the server stops accepting requests and the open requests finish, for example to flush the last batch of
+    /// metrics and traces from your OpenTelemetry exporter. The server shuts down gracefully on `Ctrl+C` and `SIGTERM`.
+    ///
+    /// The callback runs automatically when the app is launched with `dioxus::launch`. If you serve the router yourself, call
+    /// [`ServeConfig::shutdown`] after your server stops.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().on_shutdown(|| async move {
+    ///         // Flush your exporter here
+    ///         println!("Shutting down");
+    ///     }));
+    /// }
+    /// ```
+    pub fn on_shutdown<F, Fut>(mut self, on_shutdown: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_shutdown = Some(Arc::new(move || {
+            Box::pin(on_shutdown()) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }));
+        self
+    }
+
+    /// Set the source of the current time for time dependent features like token expiration. (defaults to [`SystemTimeSource`])
+    ///
+    /// This is mainly useful for injecting a fixed time in tests.
+    pub fn time_source(mut self, time_source: impl TimeSource) -> Self {
+        self.handler.time_source = Arc::new(time_source);
+        self
+    }
+
+    /// Set the source of randomness for features like nonces and tokens. (defaults to [`OsRandSource`])
+    ///
+    /// This is mainly useful for injecting fixed values in tests. In production, the source must be cryptographically secure.
+    pub fn rand_source(mut self, rand_source: impl RandSource) -> Self {
+        self.rand_source = Some(Arc::new(rand_source));
+        self
+    }
+
+    /// Inline some critical CSS into the head of the page. Critical styles are render blocking, so they should only contain the styles
+    /// needed for the content above the fold. Styles for the rest of the page can be loaded with [`Self::deferred_stylesheet`].
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(
+    ///         ServeConfigBuilder::default()
+    ///             .critical_style("header { height: 64px; }")
+    ///             .deferred_stylesheet("/assets/main.css"),
+    ///     );
+    /// }
+    /// ```
+    pub fn critical_style(mut self, css: impl Into<String>) -> Self {
+        self.critical_styles.push(css.into());
+        self
+    }
+
+    /// Load a stylesheet without blocking the first paint. The stylesheet is preloaded and applied once it finishes loading. If
+    /// javascript is disabled, the stylesheet is loaded normally.
+    pub fn deferred_stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.deferred_stylesheets.push(href.into());
+        self
+    }
+
+    /// Preload the ES modules the client needs to hydrate. Every module in the graph of the manifest's entries is sent as a
+    /// `<link rel="modulepreload">` in the head and in a `Link` header so the browser can fetch the whole graph in parallel.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     use dioxus::fullstack::ModuleManifest;
+    ///
+    ///     let manifest = ModuleManifest::new(["/assets/main.js"])
+    ///         .module("/assets/main.js", ["/assets/router.js", "/assets/runtime.js"])
+    ///         .module("/assets/router.js", ["/assets/runtime.js"])
+    ///         .module("/assets/runtime.js", Vec::<String>::new());
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().module_manifest(manifest));
+    /// }
+    /// ```
+    pub fn module_manifest(mut self, manifest: ModuleManifest) -> Self {
+        self.module_manifest = Some(manifest);
+        self
+    }
+
+    /// Embed a checksum of the structure of the rendered html that the client can compare against while hydrating. (defaults to [`HydrationCheck::Off`])
+    ///
+    /// Hydration mismatches happen when the client renders different elements than the server. They are hard to track down because
+    /// hydration usually fails far away from the component that caused the mismatch. With the check enabled, client code can detect
+    /// the mismatch up front by comparing the checksum against the elements it is about to hydrate. The built-in web renderer does
+    /// not read the checksum.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     use dioxus::fullstack::{HydrationCheck, HydrationMismatchPolicy};
+    ///     cfg = cfg.with_server_cfg(
+    ///         ServeConfigBuilder::default()
+    ///             .hydration_check(HydrationCheck::SuspenseBoundary)
+    ///             .hydration_mismatch_policy(HydrationMismatchPolicy::Rerender),
+    ///     );
+    /// }
+    /// ```
+    pub fn hydration_check(mut self, check: HydrationCheck) -> Self {
+        self.hydration_check = check;
+        self
+    }
+
+    /// Set the policy that is embedded next to the hydration checksums. This is only a marker for client code that reads the
+    /// checksums. The server and the built-in web renderer never act on it. This has no effect unless [`Self::hydration_check`]
+    /// is enabled. (defaults to [`HydrationMismatchPolicy::Warn`])
+    pub fn hydration_mismatch_policy(mut self, policy: HydrationMismatchPolicy) -> Self {
+        self.hydration_mismatch_policy = policy;
+        self
+    }
+
+    /// Serve clean static html without hydration data to crawlers that don't run javascript. A request is from one of these crawlers
+    /// if its `User-Agent` header contains any of the `user_agents` (ignoring case).
+    ///
+    /// For these requests, every suspense boundary is resolved before the page is sent in order. The streaming bootstrap script,
+    /// the serialized hydration data and the query cache are left out of the page. Scripts from your index.html are kept.
+    /// Stripped pages are never read from or written to the incremental cache.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(
+    ///         ServeConfigBuilder::default().crawler_strip_hydration(["bingbot", "duckduckbot", "facebookexternalhit"]),
+    ///     );
+    /// }
+    /// ```
+    pub fn crawler_strip_hydration(
+        mut self,
+        user_agents: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.crawler_user_agents = user_agents
+            .into_iter()
+            .map(|user_agent| user_agent.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Serve the pages whose path starts with `prefix` as static html for every request. The pages are rendered like pages for the
+    /// crawlers of [`Self::crawler_strip_hydration`], without hydration data, and they are sent even if the `Accept` header of the request
+    /// doesn't include `text/html`. Every request gets the same html, so unlike pages for crawlers, static pages go through the
+    /// incremental cache.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().static_page("/sitemap"));
+    /// }
+    /// ```
+    pub fn static_page(mut self, prefix: impl Into<String>) -> Self {
+        self.static_page_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Indent the rendered html so it is easier to read in the view source of the browser. This only has an effect in debug builds.
+    /// Release builds always send compact html. (defaults to false)
+    ///
+    /// Line breaks are only added between tags, never inside text or `<pre>`, `<textarea>`, `<script>` and `<style>` elements. The
+    /// whitespace is ignored by hydration, but it can add small gaps between inline elements.
+    ///
+    /// Only whole pages are indented, like pages without suspense, pages from the incremental cache and pages rendered with
+    /// [`RenderStrategy::Buffered`](crate::prelude::RenderStrategy::Buffered). Streamed chunks are sent compact.
+    pub fn pretty_html(mut self, pretty_html: bool) -> Self {
+        self.pretty_html = pretty_html;
+        self
+    }
+
+    /// Let a single request skip the incremental cache by sending the `X-Dioxus-Cache: bypass` header. The page is rendered fresh and
+    /// is not read from or written to the cache. This is useful to investigate stale pages without disabling the cache for everyone.
+    ///
+    /// Anyone can send the header, so it is only honored in debug builds. Release builds always ignore it. (defaults to false)
+    pub fn cache_bypass_header(mut self, enabled: bool) -> Self {
+        self.cache_bypass_header = enabled;
+        self
+    }
+
+    /// Limit how long a streamed page can take to finish. If a timeout is hit, the pending suspense boundaries are dropped and the page
+    /// is ended with a `<template id="dioxus-stream-timeout">` marker followed by the rest of the index.html after `</body>`. The
+    /// timeout is logged as a warning. These timeouts only apply to pages that are sent with the streaming render strategy, and they
+    /// are ignored on wasm servers. (defaults to no timeouts)
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use std::time::Duration;
+    /// # fn app() -> Element { todo!() }
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     use dioxus::fullstack::StreamTimeouts;
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().stream_timeouts(
+    ///         StreamTimeouts::new()
+    ///             .total(Duration::from_secs(30))
+    ///             .idle(Duration::from_secs(10)),
+    ///     ));
+    /// }
+    /// ```
+    pub fn stream_timeouts(mut self, stream_timeouts: StreamTimeouts) -> Self {
+        self.stream_timeouts = stream_timeouts;
+        self
+    }
+
+    /// Capture the `tracing` events at `level` or more severe that components log while a page renders. The lines are added to the
+    /// response of the page as [`RenderLogs`](crate::prelude::RenderLogs). Every render collects its own lines, so concurrent renders
+    /// never mix their logs.
+    ///
+    /// While a page renders, its events are only captured and not sent to the global subscriber. Use this to debug renders in
+    /// development. Pages served from the incremental cache are not rendered, so they have no logs.
+    pub fn capture_render_logs(mut self, level: tracing::Level) -> Self {
+        self.capture_render_logs = Some(level);
+        self
+    }
+
+    /// Add `loading="lazy"` and `decoding="async"` to every `<img>` in the rendered html that doesn't set them already. Mark images that
+    /// are critical for the first paint, like a hero image, with a `data-priority` attribute to load them normally. (defaults to false)
+    pub fn auto_lazy_images(mut self, auto_lazy_images: bool) -> Self {
+        self.auto_lazy_images = auto_lazy_images;
+        self
+    }
+
+    /// Set the locales the app supports. The locale of each page request is negotiated from the `Accept-Language` header and read
+    /// with [`DioxusServerContext::locale`]. If none of the languages the client accepts are supported, the first locale is used.
+    ///
+    /// The `lang` and `dir` attributes of the `<html>` element are set from the negotiated locale. Languages in
+    /// [`Self::rtl_languages`] get `dir="rtl"`.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().locales(["en-US", "fr", "ar"]));
+    /// }
+    /// ```
+    pub fn locales(mut self, locales: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.locales = locales.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the languages that are written right to left, like `ar` or `he`. Only the language part of a locale is compared, so `ar`
+    /// also matches `ar-EG`. (defaults to the common right to left languages)
+    pub fn rtl_languages(mut self, languages: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.rtl_languages = languages
+            .into_iter()
+            .map(|language| language.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Make every page declare its character set and language. The head of the index.html starts with `<meta charset="utf-8">`,
+    /// which the browser must see before any other content to parse the page correctly, and other `<meta charset>` tags are removed.
+    /// If the `<html>` element has no `lang` attribute and no locale was negotiated with [`Self::locales`], the page gets `lang="en"`.
+    /// (defaults to true)
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     // Leave the head and the `<html>` element of the index.html as they are
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().charset_and_lang(false));
+    /// }
+    /// ```
+    pub fn charset_and_lang(mut self, charset_and_lang: bool) -> Self {
+        self.charset_and_lang = charset_and_lang;
+        self
+    }
+
+    /// Set a function that assigns the variant of each feature flag for a request. Components read the assignment with
+    /// [`use_variants`](crate::prelude::use_variants) while rendering, and the assignment is serialized into the page so the client
+    /// hydrates with the same variants.
+    ///
+    /// Use [`Variants::bucket`] with a stable key like a user id to keep the assignment consistent across requests. Pages with
+    /// assigned variants are never read from or written to the incremental cache.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().variants(|parts| {
+    ///         let user = parts.headers.get("x-user-id").and_then(|id| id.to_str().ok());
+    ///         match user.and_then(|user| Variants::bucket(user, "checkout", &["classic", "one-page"])) {
+    ///             Some(variant) => Variants::new().with("checkout", variant),
+    ///             None => Variants::new(),
+    ///         }
+    ///     }));
+    /// }
+    /// ```
+    pub fn variants(
+        mut self,
+        assign: impl Fn(&http::request::Parts) -> Variants + Send + Sync + 'static,
+    ) -> Self {
+        self.variants = Some(Arc::new(assign));
+        self
+    }
+
+    /// Render each page in the color theme stored in the `cookie`, like `dark` or `light`. The theme is read with
+    /// [`use_theme`](crate::prelude::use_theme) and written to the `data-theme` and `class` attributes of the `<html>` element so
+    /// the page never flashes in the wrong theme. The `class` attribute is only set if the index.html doesn't set one already.
+    ///
+    /// Without the cookie, the theme from the `Sec-CH-Prefers-Color-Scheme` client hint is used, and then `default`. Only pages in
+    /// the `default` theme are read from and written to the incremental cache.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().theme_cookie("theme", "light"));
+    /// }
+    /// ```
+    pub fn theme_cookie(mut self, cookie: impl Into<String>, default: impl Into<String>) -> Self {
+        self.theme_cookie = Some(ThemeCookie {
+            name: cookie.into(),
+            default: default.into(),
+        });
+        self
+    }
+
+    /// Set a function that checks if a request is from an authenticated user, for example by looking for a session cookie.
+    ///
+    /// Pages for authenticated users are personalized, so they must not be stored by shared caches. These pages are sent with
+    /// `Cache-Control: private, no-store` and are never read from or written to the incremental cache. Pages for anonymous users use the
+    /// normal cache policy.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().authenticated(|parts| {
+    ///         parts
+    ///             .headers
+    ///             .get_all(http::header::COOKIE)
+    ///             .iter()
+    ///             .filter_map(|cookie| cookie.to_str().ok())
+    ///             .any(|cookie| cookie.split(';').any(|cookie| cookie.trim().starts_with("session=")))
+    ///     }));
+    /// }
+    /// ```
+    pub fn authenticated(
+        mut self,
+        is_authenticated: impl Fn(&http::request::Parts) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.authenticated = Some(Arc::new(is_authenticated));
+        self
+    }
+
+    /// Send a `Content-Security-Policy` header with every page. If the app sets the header itself with
+    /// [`DioxusServerContext::response_parts_mut`], the header from the app is used instead.
+    pub fn content_security_policy(mut self, policy: impl Into<String>) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self
+    }
+
+    /// Send an `X-Robots-Tag` header like `noindex` with every page to tell search engines how to index the pages. This is useful to keep
+    /// a staging deployment out of search results. Pages can override the directive with [`DioxusServerContext::set_robots`].
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     if std::env::var("STAGING").is_ok() {
+    ///         cfg = cfg.with_server_cfg(ServeConfigBuilder::default().robots("noindex, nofollow"));
+    ///     }
+    /// }
+    /// ```
+    pub fn robots(mut self, directive: impl Into<String>) -> Self {
+        self.robots = Some(directive.into());
+        self
+    }
+
+    /// Send a `Permissions-Policy` header with every page to restrict the browser features the page and its iframes can use. Pages can
+    /// enable more origins for a feature with [`DioxusServerContext::allow_permission`]. If the app sets the header itself with
+    /// [`DioxusServerContext::response_parts_mut`], the header from the app is used instead.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     use dioxus::fullstack::PermissionsPolicy;
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().permissions_policy(
+    ///         PermissionsPolicy::new()
+    ///             .deny("camera")
+    ///             .deny("microphone")
+    ///             .allow("geolocation", ["self"]),
+    ///     ));
+    /// }
+    /// ```
+    pub fn permissions_policy(mut self, policy: PermissionsPolicy) -> Self {
+        self.permissions_policy = Some(policy);
+        self
+    }
+
+    /// Generate a nonce for every page request and add it to each `<script>` and `<style>` element in the page, including the hydration
+    /// scripts of the renderer and elements rendered by components. The nonce is added to the `script-src` and `style-src` directives
+    /// of the content security policy, so the policy can forbid any other inline scripts and styles. (defaults to false)
+    ///
+    /// Components can read the nonce with [`DioxusServerContext::nonce`]. Without a [`Self::content_security_policy`] or a policy set by
+    /// the app, the nonce is added to the page but nothing is enforced.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(
+    ///         ServeConfigBuilder::default()
+    ///             .content_security_policy("default-src 'self'; script-src 'self' 'wasm-unsafe-eval'")
+    ///             .csp_nonce(true),
+    ///     );
+    /// }
+    /// ```
+    pub fn csp_nonce(mut self, csp_nonce: bool) -> Self {
+        self.csp_nonce = csp_nonce;
+        self
+    }
+
+    /// Set what happens when the app renders no content other than whitespace and comments. This usually means a bug in the app, but
+    /// the page would still be sent with `200 OK`. (defaults to [`EmptyRenderPolicy::Allow`])
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// server_only! {
+    ///     use dioxus::fullstack::EmptyRenderPolicy;
+    ///
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().empty_render(EmptyRenderPolicy::Error));
+    /// }
+    /// ```
+    pub fn empty_render(mut self, policy: EmptyRenderPolicy) -> Self {
+        self.empty_render = policy;
+        self
+    }
+
+    /// Set the maximum number of `Set-Cookie` headers the server function handler sends in one response. Any extra cookies are
+    /// logged and dropped. This protects clients from huge responses if a code path sets cookies in a loop.
+    /// (defaults to [`DEFAULT_MAX_SET_COOKIE_HEADERS`](crate::prelude::DEFAULT_MAX_SET_COOKIE_HEADERS))
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().max_set_cookie_headers(16));
+    /// }
+    /// ```
+    pub fn max_set_cookie_headers(mut self, max: usize) -> Self {
+        self.handler.max_set_cookie_headers = max;
+        self
+    }
+
+    /// Set the maximum size of a server function request body in bytes. Requests with a larger `Content-Length` are rejected with
+    /// `413 Payload Too Large` before the body is read, so clients that send `Expect: 100-continue` never upload it. Bodies without a
+    /// `Content-Length` fail once they grow past the limit. (defaults to no limit)
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     // Allow uploads up to 10 MB
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().max_server_fn_body_size(10 * 1024 * 1024));
+    /// }
+    /// ```
+    pub fn max_server_fn_body_size(mut self, max: usize) -> Self {
+        self.handler.max_server_fn_body_size = Some(max);
+        self
+    }
+
+    /// Buffer the form-urlencoded body of requests to the server function `F` so it can read the form with
+    /// [`DioxusServerContext::form`](crate::prelude::DioxusServerContext::form). Bodies of other server functions are never
+    /// buffered.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// #[server]
+    /// async fn signup() -> Result<(), ServerFnError> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().read_server_fn_form::<Signup>());
+    /// }
+    /// ```
+    pub fn read_server_fn_form<F: server_fn::ServerFn>(mut self) -> Self {
+        self.handler.form_server_fns.insert(F::PATH);
+        self
+    }
+
+    /// Set the maximum size in bytes of a form-urlencoded body that is buffered for
+    /// [`DioxusServerContext::form`](crate::prelude::DioxusServerContext::form). Larger forms are rejected with
+    /// `413 Payload Too Large` before the server function runs.
+    /// (defaults to [`DEFAULT_MAX_FORM_BODY_SIZE`](crate::prelude::DEFAULT_MAX_FORM_BODY_SIZE))
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().max_form_body_size(64 * 1024));
+    /// }
+    /// ```
+    pub fn max_form_body_size(mut self, max: usize) -> Self {
+        self.handler.max_form_body_size = max;
+        self
+    }
+
+    /// Set the maximum number of responses the server function cache holds. Server functions opt into the cache with
+    /// [`DioxusServerContext::cache_response_for`](crate::prelude::DioxusServerContext::cache_response_for). Once the cache is full,
+    /// the least recently used response is removed. A capacity of zero disables the cache, but the cache headers are still sent.
+    /// (defaults to [`DEFAULT_SERVER_FN_CACHE_CAPACITY`](crate::prelude::DEFAULT_SERVER_FN_CACHE_CAPACITY))
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().server_fn_cache_capacity(10_000));
+    /// }
+    /// ```
+    pub fn server_fn_cache_capacity(mut self, capacity: usize) -> Self {
+        self.handler.server_fn_cache =
+            Arc::new(crate::server::server_fn_cache::ServerFnCache::new(capacity));
+        self
+    }
+
+    /// Set the budget in bytes for the headers of the responses of the render handler and the server function handler. Proxies and
+    /// clients reject responses with headers that are too large, often with a confusing error. If the headers of a response are larger
+    /// than the budget, a warning is logged and optional headers like the `Link` preloads are dropped until the headers fit. The status,
+    /// `Content-Type`, `Set-Cookie` and every other header are always kept.
+    /// (defaults to [`DEFAULT_MAX_RESPONSE_HEADER_SIZE`](crate::prelude::DEFAULT_MAX_RESPONSE_HEADER_SIZE))
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     // Stay under the 8 KB default buffer of many proxies
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().max_response_header_size(8 * 1024));
+    /// }
+    /// ```
+    pub fn max_response_header_size(mut self, max: usize) -> Self {
+        self.handler.max_response_header_size = max;
+        self
+    }
+
+    /// Set where [`DioxusServerContext::client_ip`](crate::prelude::DioxusServerContext::client_ip) reads the address of the client
+    /// from. By default, only the address of the socket is used.
+    ///
+    /// Headers like `X-Forwarded-For` can be set by anyone, so they are only read if the socket address is one of the trusted proxies.
+    /// The socket address is only available if the app is served with
+    /// [`into_make_service_with_connect_info::<SocketAddr>`](axum::Router::into_make_service_with_connect_info).
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(
+    ///         ServeConfigBuilder::default().client_ip_source(
+    ///             ClientIpSource::new()
+    ///                 .trusted_header(http::HeaderName::from_static("cf-connecting-ip"))
+    ///                 .trusted_header(http::HeaderName::from_static("x-forwarded-for"))
+    ///                 .trusted_proxy("10.0.0.0/8".parse().unwrap())
+    ///                 .trusted_proxy("127.0.0.1/32".parse().unwrap()),
+    ///         ),
+    ///     );
+    /// }
+    /// ```
+    pub fn client_ip_source(mut self, source: crate::server::ClientIpSource) -> Self {
+        self.handler.client_ip_source = source;
+        self
+    }
+
+    /// Set a callback that is run with the path and the error every time a server function fails. This gives you one place to log or
+    /// report errors from every server function. Setting a new callback replaces the previous one.
+    ///
+    /// The callback runs before the error is mapped with [`Self::register_server_fn_error`]. Custom errors are passed as
+    /// [`ServerFnError::ServerError`](server_fn::ServerFnError::ServerError) with their `Display` output.
     ///
     /// ```rust, no_run
-    /// # fn app() -> Element { todo!() }
     /// use dioxus::prelude::*;
     ///
     /// let mut cfg = dioxus::fullstack::Config::new();
     ///
-    /// // Only set the server config if the server feature is enabled
     /// server_only! {
-    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().root_id("app"));
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().on_server_fn_error(|path, err| {
+    ///         tracing::error!("Server function {path} failed: {err}");
+    ///     }));
     /// }
+    /// ```
+    pub fn on_server_fn_error(
+        mut self,
+        logger: impl Fn(&str, &server_fn::ServerFnError) + Send + Sync + 'static,
+    ) -> Self {
+        self.handler.error_logger = Some(Arc::new(logger));
+        self
+    }
+
+    /// Register the response that is sent when a server function fails with a custom error of type `E`. Without a mapping, every
+    /// error is sent as a `500 Internal Server Error` with the serialized [`ServerFnError`](server_fn::ServerFnError) as the body.
     ///
-    /// // You also need to set the root id in your web config
-    /// web! {
-    ///     cfg = cfg.with_web_config(dioxus::web::Config::default().rootname("app"));
+    /// The mapping only applies to [`ServerFnError::WrappedServerError`](server_fn::ServerFnError::WrappedServerError). The body you
+    /// return replaces the serialized error, so clients that need to read the error should use a format they can parse. If multiple
+    /// mappings can parse an error, the mapping that was registered first is used.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
+    ///
+    /// #[derive(Debug, Clone)]
+    /// enum ApiError {
+    ///     NotFound,
+    ///     Validation(String),
     /// }
     ///
-    /// // And desktop config
-    /// desktop! {
-    ///     cfg = cfg.with_desktop_config(dioxus::desktop::Config::default().with_root_name("app"));
+    /// impl std::fmt::Display for ApiError {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         match self {
+    ///             ApiError::NotFound => write!(f, "not found"),
+    ///             ApiError::Validation(message) => write!(f, "invalid: {message}"),
+    ///         }
+    ///     }
     /// }
     ///
-    /// // Finally, launch the app with the config
-    /// LaunchBuilder::new()
-    ///     .with_cfg(cfg)
-    ///     .launch(app);
+    /// impl std::str::FromStr for ApiError {
+    ///     type Err = ();
+    ///
+    ///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+    ///         match s {
+    ///             "not found" => Ok(ApiError::NotFound),
+    ///             _ => s
+    ///                 .strip_prefix("invalid: ")
+    ///                 .map(|message| ApiError::Validation(message.to_string()))
+    ///                 .ok_or(()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     use axum::body::Body;
+    ///     use http::StatusCode;
+    ///
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().register_server_fn_error(
+    ///         |err: &ApiError| match err {
+    ///             ApiError::NotFound => (StatusCode::NOT_FOUND, Body::from("not found")),
+    ///             ApiError::Validation(message) => {
+    ///                 (StatusCode::UNPROCESSABLE_ENTITY, Body::from(message.clone()))
+    ///             }
+    ///         },
+    ///     ));
+    /// }
     /// ```
-    pub fn root_id(mut self, root_id: &'static str) -> Self {
-        self.root_id = Some(root_id);
+    pub fn register_server_fn_error<E>(
+        mut self,
+        mapping: impl Fn(&E) -> (http::StatusCode, axum::body::Body) + Send + Sync + 'static,
+    ) -> Self
+    where
+        E: std::str::FromStr + std::fmt::Display + 'static,
+    {
+        self.handler
+            .error_mappings
+            .push(crate::server::server_fn_errors::error_mapping(mapping));
         self
     }
 
-    /// Provide context to the root and server functions. You can use this context
-    /// while rendering with [`consume_context`](dioxus_lib::prelude::consume_context) or in server functions with [`FromContext`](crate::prelude::FromContext).
-    ///
-    /// Context will be forwarded from the LaunchBuilder if it is provided.
+    /// Normalize the `Host` header and the authority of the uri of every request before the render handler and the server function
+    /// handler read them. Code that reads the host from the request parts, like
+    /// [`DioxusServerContext::host`](crate::prelude::DioxusServerContext::host), sees the normalized host. By default, the host is not
+    /// changed.
     ///
     /// ```rust, no_run
     /// use dioxus::prelude::*;
     ///
-    /// dioxus::LaunchBuilder::new()
-    ///     // You can provide context to your whole app (including server functions) with the `with_context` method on the launch builder
-    ///     .with_context(server_only! {
-    ///         1234567890u32
-    ///     })
-    ///     .launch(app);
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     // `Example.com:443` and `example.com` are now the same host
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().host_normalization(HostNormalization::new()));
+    /// }
+    /// ```
+    pub fn host_normalization(mut self, normalization: crate::server::HostNormalization) -> Self {
+        self.handler.host_normalization = Some(normalization);
+        self
+    }
+
+    /// Transform the decoded arguments of the server function `F` before it runs. Use this to normalize requests, like trimming
+    /// strings, without changing the body of every server function. Registering a new transform for `F` replaces the previous one.
+    ///
+    /// The transform runs inside the server context of the request, so it can read the request with
+    /// [`server_context`](crate::prelude::server_context). Arguments are transformed for the `GetUrl`, `PostUrl` and `Json` input
+    /// encodings. Requests in other encodings, and requests that can't be decoded, are passed to the server function unchanged so it
+    /// reports its usual error.
+    ///
+    /// The body is buffered to transform it, up to the limit set with [`Self::max_server_fn_body_size`] or 2 MB if no limit is set.
+    /// Larger bodies are rejected with `413 Payload Too Large`.
+    ///
+    /// Cached responses from [`DioxusServerContext::cache_response_for`](crate::prelude::DioxusServerContext::cache_response_for) are
+    /// looked up with the arguments the client sent, before they are transformed.
+    ///
+    /// ```rust, no_run
+    /// use dioxus::prelude::*;
     ///
     /// #[server]
-    /// async fn read_context() -> Result<u32, ServerFnError> {
-    ///     // You can extract values from the server context with the `extract` function
-    ///     let FromContext(value) = extract().await?;
-    ///     Ok(value)
+    /// async fn subscribe(email: String) -> Result<(), ServerFnError> {
+    ///     Ok(())
     /// }
     ///
-    /// fn app() -> Element {
-    ///     let future = use_resource(read_context);
-    ///     rsx! {
-    ///         h1 { "{future:?}" }
-    ///     }
+    /// let mut cfg = dioxus::fullstack::Config::new();
+    ///
+    /// server_only! {
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().transform_server_fn_args(
+    ///         |args: Subscribe| Subscribe {
+    ///             email: args.email.trim().to_lowercase(),
+    ///         },
+    ///     ));
     /// }
     /// ```
-    pub fn context_providers(mut self, state: ContextProviders) -> Self {
-        self.context_providers = state;
+    pub fn transform_server_fn_args<F>(
+        mut self,
+        transform: impl Fn(F) -> F + Send + Sync + 'static,
+    ) -> Self
+    where
+        F: server_fn::ServerFn + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.handler.server_fn_args.insert(
+            F::PATH,
+            crate::server::server_fn_args::args_transform(transform),
+        );
         self
     }
 
@@ -170,12 +1157,47 @@ impl ServeConfigBuilder {
             None => load_index_path(index_path)?,
         };
 
-        let index = load_index_html(index_html, root_id);
+        let mut index = load_index_html(index_html, root_id);
+        if self.charset_and_lang {
+            index.put_charset_first();
+        }
 
         Ok(ServeConfig {
             index,
             incremental: self.incremental,
             context_providers: self.context_providers,
+            route_pattern: self.route_pattern,
+            span_name: self.span_name,
+            prefetch_queries: self.prefetch_queries,
+            rand_source: self.rand_source.unwrap_or_else(|| Arc::new(OsRandSource)),
+            critical_styles: self.critical_styles,
+            deferred_stylesheets: self.deferred_stylesheets,
+            hydration_check: self.hydration_check,
+            hydration_mismatch_policy: self.hydration_mismatch_policy,
+            crawler_user_agents: self.crawler_user_agents,
+            pretty_html: self.pretty_html && cfg!(debug_assertions),
+            auto_lazy_images: self.auto_lazy_images,
+            locales: self.locales,
+            rtl_languages: self.rtl_languages,
+            authenticated: self.authenticated,
+            module_preloads: self
+                .module_manifest
+                .map(|manifest| manifest.preloads())
+                .unwrap_or_default(),
+            content_security_policy: self.content_security_policy,
+            csp_nonce: self.csp_nonce,
+            empty_render: self.empty_render,
+            variants: self.variants,
+            theme_cookie: self.theme_cookie,
+            on_shutdown: self.on_shutdown,
+            cache_bypass_header: self.cache_bypass_header && cfg!(debug_assertions),
+            stream_timeouts: self.stream_timeouts,
+            permissions_policy: self.permissions_policy,
+            capture_render_logs: self.capture_render_logs,
+            robots: self.robots,
+            static_page_prefixes: self.static_page_prefixes,
+            charset_and_lang: self.charset_and_lang,
+            handler: Arc::new(self.handler),
         })
     }
 }
@@ -274,6 +1296,127 @@ pub(crate) struct IndexHtml {
     pub(crate) after_closing_body_tag: String,
 }
 
+/// The charset every page is declared with. See [`ServeConfigBuilder::charset_and_lang`]
+const META_CHARSET: &str = r#"<meta charset="utf-8">"#;
+
+impl IndexHtml {
+    /// Remove the `<meta charset>` tags of the template and declare the charset as the first element of the head instead
+    fn put_charset_first(&mut self) {
+        remove_meta_charset(&mut self.head_before_title);
+        remove_meta_charset(&mut self.head_after_title);
+        // Without a title, the whole head is in the part after the title
+        let head = match head_content_start(&self.head_before_title) {
+            Some(start) => Some((&mut self.head_before_title, start)),
+            None => head_content_start(&self.head_after_title)
+                .map(|start| (&mut self.head_after_title, start)),
+        };
+        if let Some((head, start)) = head {
+            head.insert_str(start, META_CHARSET);
+        }
+    }
+}
+
+/// Find the position right after the opening `<head>` tag
+fn head_content_start(html: &str) -> Option<usize> {
+    find_opening_tag(html, "head").map(|(start, tag)| start + tag.source().len())
+}
+
+/// Find the first opening tag of the element `name` and where it starts in some html
+pub(crate) fn find_opening_tag<'a>(html: &'a str, name: &str) -> Option<(usize, Tag<'a>)> {
+    let mut start = 0;
+    for token in Tokens::new(html) {
+        match token {
+            Token::Tag(tag) if tag.is(name) && !tag.is_closing() => return Some((start, tag)),
+            token => start += token.source().len(),
+        }
+    }
+    None
+}
+
+/// Remove every `<meta>` tag with a `charset` attribute
+fn remove_meta_charset(html: &mut String) {
+    *html = Tokens::new(html)
+        .filter(|token| {
+            !matches!(token, Token::Tag(tag) if tag.is("meta") && tag.attribute("charset").is_some())
+        })
+        .map(|token| token.source())
+        .collect();
+}
+
+#[test]
+fn nonces_come_from_the_rand_source() {
+    struct FixedRand;
+
+    impl RandSource for FixedRand {
+        fn fill_bytes(&self, dest: &mut [u8]) {
+            dest.fill(1);
+        }
+    }
+
+    let cfg = ServeConfigBuilder::new()
+        .index_html(r#"<html><head></head><body><div id="main"></div></body></html>"#.to_string())
+        .csp_nonce(true)
+        .rand_source(FixedRand)
+        .build()
+        .unwrap();
+    assert_eq!(
+        cfg.generate_nonce().as_deref(),
+        Some("AQEBAQEBAQEBAQEBAQEBAQ==")
+    );
+}
+
+#[test]
+fn default_builder_puts_charset_first() {
+    let cfg = ServeConfigBuilder::default()
+        .index_html(
+            r#"<!DOCTYPE html><html><head><title>App</title><meta charset="UTF-8"></head><body><div id="main"></div></body></html>"#
+                .to_string(),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(
+        cfg.index.head_before_title,
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8">"#
+    );
+}
+
+#[test]
+fn charset_precedes_head_content() {
+    let mut index = load_index_html(
+        r#"<!DOCTYPE html><html><head><title>App</title><meta name="viewport" content="width=device-width"><meta charset="UTF-8" /></head><body><div id="main"></div></body></html>"#.to_string(),
+        "main",
+    );
+    index.put_charset_first();
+    assert_eq!(
+        index.head_before_title,
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8">"#
+    );
+    assert!(!index.head_after_title.contains("charset"));
+
+    // The title is optional, so the head may only be in the part after the title
+    let mut index = load_index_html(
+        r#"<html><head ><meta charset="latin1"><header></header></head><body><div id="main"></div></body></html>"#.to_string(),
+        "main",
+    );
+    index.put_charset_first();
+    assert!(index
+        .head_after_title
+        .starts_with(r#"<html><head ><meta charset="utf-8"><header>"#));
+}
+
+#[test]
+fn validate_finds_uppercase_html_tag() {
+    let cfg = ServeConfigBuilder::default()
+        .index_html(
+            r#"<!DOCTYPE html><HTML><head><title>App</title></head><body><div id="main"></div></body></HTML>"#
+                .to_string(),
+        )
+        .locales(["en"])
+        .build()
+        .unwrap();
+    assert!(cfg.validate().is_empty());
+}
+
 /// Used to configure how to serve a Dioxus application. It contains information about how to serve static assets, and what content to render with [`dioxus-ssr`].
 /// See [`ServeConfigBuilder`] to create a ServeConfig
 #[derive(Clone)]
@@ -281,6 +1424,73 @@ pub struct ServeConfig {
     pub(crate) index: IndexHtml,
     pub(crate) incremental: Option<dioxus_isrg::IncrementalRendererConfig>,
     pub(crate) context_providers: ContextProviders,
+    pub(crate) route_pattern: Option<RoutePatternFn>,
+    pub(crate) span_name: Option<SpanNameFn>,
+    pub(crate) prefetch_queries: Option<PrefetchQueriesFn>,
+    pub(crate) rand_source: Arc<dyn RandSource>,
+    pub(crate) critical_styles: Vec<String>,
+    pub(crate) deferred_stylesheets: Vec<String>,
+    pub(crate) hydration_check: HydrationCheck,
+    pub(crate) hydration_mismatch_policy: HydrationMismatchPolicy,
+    pub(crate) crawler_user_agents: Vec<String>,
+    pub(crate) pretty_html: bool,
+    pub(crate) auto_lazy_images: bool,
+    pub(crate) locales: Vec<String>,
+    pub(crate) rtl_languages: Vec<String>,
+    pub(crate) authenticated: Option<AuthenticatedFn>,
+    pub(crate) module_preloads: Vec<String>,
+    pub(crate) content_security_policy: Option<String>,
+    pub(crate) csp_nonce: bool,
+    pub(crate) empty_render: EmptyRenderPolicy,
+    pub(crate) variants: Option<VariantsFn>,
+    pub(crate) theme_cookie: Option<ThemeCookie>,
+    pub(crate) on_shutdown: Option<OnShutdownFn>,
+    pub(crate) cache_bypass_header: bool,
+    pub(crate) stream_timeouts: StreamTimeouts,
+    pub(crate) permissions_policy: Option<PermissionsPolicy>,
+    pub(crate) capture_render_logs: Option<tracing::Level>,
+    pub(crate) robots: Option<String>,
+    pub(crate) static_page_prefixes: Vec<String>,
+    pub(crate) charset_and_lang: bool,
+    pub(crate) handler: Arc<HandlerConfig>,
+}
+
+/// The settings the server function handler reads as well as the render handler. Server functions that are registered without a
+/// [`ServeConfig`] use the defaults.
+#[derive(Clone)]
+pub(crate) struct HandlerConfig {
+    pub(crate) time_source: Arc<dyn TimeSource>,
+    pub(crate) max_set_cookie_headers: usize,
+    pub(crate) max_server_fn_body_size: Option<usize>,
+    pub(crate) max_response_header_size: usize,
+    pub(crate) max_form_body_size: usize,
+    pub(crate) client_ip_source: crate::server::ClientIpSource,
+    pub(crate) error_mappings: Vec<crate::server::server_fn_errors::ErrorMapping>,
+    pub(crate) error_logger: Option<crate::server::server_fn_errors::ErrorLogger>,
+    pub(crate) host_normalization: Option<crate::server::HostNormalization>,
+    pub(crate) server_fn_cache: Arc<crate::server::server_fn_cache::ServerFnCache>,
+    pub(crate) form_server_fns: std::collections::HashSet<&'static str>,
+    pub(crate) server_fn_args:
+        std::collections::HashMap<&'static str, crate::server::server_fn_args::ArgsTransform>,
+}
+
+impl Default for HandlerConfig {
+    fn default() -> Self {
+        Self {
+            time_source: Arc::new(SystemTimeSource),
+            max_set_cookie_headers: crate::server::DEFAULT_MAX_SET_COOKIE_HEADERS,
+            max_server_fn_body_size: None,
+            max_response_header_size: crate::server::DEFAULT_MAX_RESPONSE_HEADER_SIZE,
+            max_form_body_size: crate::server::DEFAULT_MAX_FORM_BODY_SIZE,
+            client_ip_source: Default::default(),
+            error_mappings: Vec::new(),
+            error_logger: None,
+            host_normalization: None,
+            server_fn_cache: Default::default(),
+            form_server_fns: Default::default(),
+            server_fn_args: Default::default(),
+        }
+    }
 }
 
 impl LaunchConfig for ServeConfig {}
@@ -295,4 +1505,561 @@ impl ServeConfig {
     pub fn builder() -> ServeConfigBuilder {
         ServeConfigBuilder::new()
     }
+
+    /// Get the source of the current time this config uses
+    pub fn time_source(&self) -> &dyn TimeSource {
+        &*self.handler.time_source
+    }
+
+    /// Get the source of randomness this config uses
+    pub fn rand_source(&self) -> &dyn RandSource {
+        &*self.rand_source
+    }
+
+    /// Check the config for problems that would otherwise only show up while serving requests, like assets that are missing from the
+    /// public directory or options that cancel each other out. Call this at startup to fail fast. Returns an empty list if the
+    /// config is valid. This only reads the file system and never modifies the config.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// let cfg = ServeConfig::new().unwrap();
+    /// let problems = cfg.validate();
+    /// for problem in &problems {
+    ///     eprintln!("{problem}");
+    /// }
+    /// if !problems.is_empty() {
+    ///     std::process::exit(1);
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+
+        // The locale and theme are written to the attributes of the <html> tag
+        if find_opening_tag(&self.index.head_before_title, "html").is_none() {
+            if !self.locales.is_empty() {
+                problems.push(ConfigProblem::MissingHtmlTag { option: "locales" });
+            }
+            if self.theme_cookie.is_some() {
+                problems.push(ConfigProblem::MissingHtmlTag {
+                    option: "theme_cookie",
+                });
+            }
+        }
+
+        // Local assets are served from the public directory
+        let public_path = public_path();
+        let local_assets = self
+            .module_preloads
+            .iter()
+            .chain(&self.deferred_stylesheets)
+            .filter(|url| url.starts_with('/') && !url.starts_with("//"));
+        for url in local_assets {
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            let path = public_path.join(path.trim_start_matches('/'));
+            if !path.is_file() {
+                problems.push(ConfigProblem::MissingAsset {
+                    url: url.clone(),
+                    path,
+                });
+            }
+        }
+
+        // Browsers ignore 'unsafe-inline' in policies with a nonce
+        let allows_unsafe_inline = self
+            .content_security_policy
+            .as_ref()
+            .is_some_and(|policy| policy.contains("'unsafe-inline'"));
+        if self.csp_nonce && allows_unsafe_inline {
+            problems.push(ConfigProblem::Conflict {
+                option: "csp_nonce",
+                other: "'unsafe-inline' in content_security_policy",
+            });
+        }
+
+        if let Some(cookie) = &self.theme_cookie {
+            if !is_valid_theme(&cookie.default) {
+                problems.push(ConfigProblem::Invalid {
+                    option: "theme_cookie",
+                    reason: format!(
+                        "the default theme {:?} may only contain letters, numbers, `-` and `_`",
+                        cookie.default
+                    ),
+                });
+            }
+        }
+        if let Some(locale) = self.locales.iter().find(|locale| locale.trim().is_empty()) {
+            problems.push(ConfigProblem::Invalid {
+                option: "locales",
+                reason: format!("{locale:?} is not a language tag"),
+            });
+        }
+        if self.empty_render == EmptyRenderPolicy::Placeholder(String::new()) {
+            problems.push(ConfigProblem::Invalid {
+                option: "empty_render",
+                reason: "the placeholder is empty".to_string(),
+            });
+        }
+
+        problems
+    }
+
+    /// Run the callback set with [`ServeConfigBuilder::on_shutdown`]. Call this after the server stops if you serve the router yourself.
+    pub async fn shutdown(&self) {
+        if let Some(on_shutdown) = &self.on_shutdown {
+            on_shutdown().await;
+        }
+    }
+
+    /// Generate a new nonce for a request if nonces are enabled
+    pub(crate) fn generate_nonce(&self) -> Option<String> {
+        use base64::Engine;
+
+        if !self.csp_nonce {
+            return None;
+        }
+        let mut bytes = [0; 16];
+        self.rand_source.fill_bytes(&mut bytes);
+        Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    /// Resolve the route pattern of a path with the function from [`ServeConfigBuilder::route_pattern`]
+    pub(crate) fn resolve_route_pattern(&self, path: &str) -> Option<String> {
+        self.route_pattern
+            .as_ref()
+            .and_then(|resolve| resolve(path))
+    }
+
+    /// Get the name of the tracing span of a render. See [`ServeConfigBuilder::span_name`]
+    pub(crate) fn span_name(&self, parts: &http::request::Parts, pattern: Option<&str>) -> String {
+        match (&self.span_name, pattern) {
+            (Some(name), pattern) => name(parts, pattern),
+            (None, Some(pattern)) => pattern.to_string(),
+            (None, None) => parts.uri.path().to_string(),
+        }
+    }
+
+    /// Pick the supported locale that best matches the `Accept-Language` header of a request. Returns `None` if no locales are configured.
+    pub(crate) fn negotiate_locale(&self, parts: &http::request::Parts) -> Option<String> {
+        let default = self.locales.first()?;
+        let accept_language = parts
+            .headers
+            .get(http::header::ACCEPT_LANGUAGE)
+            .and_then(|header| header.to_str().ok())
+            .unwrap_or_default();
+
+        // Sort the accepted languages by their quality
+        let mut accepted: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|language| {
+                let mut parts = language.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|quality| quality.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        accepted.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        for (tag, _) in accepted {
+            if tag == "*" {
+                return Some(default.clone());
+            }
+            // Prefer an exact match, then a match for the language without the region
+            let language = primary_language(tag);
+            let matched = self
+                .locales
+                .iter()
+                .find(|locale| locale.eq_ignore_ascii_case(tag))
+                .or_else(|| {
+                    self.locales
+                        .iter()
+                        .find(|locale| primary_language(locale).eq_ignore_ascii_case(language))
+                });
+            if let Some(locale) = matched {
+                return Some(locale.clone());
+            }
+        }
+
+        Some(default.clone())
+    }
+
+    /// Check if a locale is written right to left
+    pub(crate) fn is_rtl(&self, locale: &str) -> bool {
+        let language = primary_language(locale).to_ascii_lowercase();
+        self.rtl_languages.contains(&language)
+    }
+
+    /// Check if a request is from an authenticated user whose pages must not be cached
+    pub(crate) fn is_authenticated(&self, parts: &http::request::Parts) -> bool {
+        self.authenticated
+            .as_ref()
+            .is_some_and(|is_authenticated| is_authenticated(parts))
+    }
+
+    /// Get the theme a request should be rendered in. Returns `None` if no theme cookie is configured.
+    pub(crate) fn resolve_theme(&self, parts: &http::request::Parts) -> Option<String> {
+        let ThemeCookie { name, default } = self.theme_cookie.as_ref()?;
+
+        let from_cookie = parts
+            .headers
+            .get_all(http::header::COOKIE)
+            .iter()
+            .filter_map(|cookie| cookie.to_str().ok())
+            .flat_map(|cookie| cookie.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, theme)| theme.trim_matches('"'))
+            .filter(|theme| is_valid_theme(theme));
+        let from_hint = || {
+            parts
+                .headers
+                .get("sec-ch-prefers-color-scheme")
+                .and_then(|hint| hint.to_str().ok())
+                .map(|hint| hint.trim().trim_matches('"'))
+                .filter(|hint| matches!(*hint, "light" | "dark"))
+        };
+
+        Some(
+            from_cookie
+                .or_else(from_hint)
+                .unwrap_or(default)
+                .to_string(),
+        )
+    }
+
+    /// Check if a theme is the theme pages are rendered in without a theme cookie
+    pub(crate) fn is_default_theme(&self, theme: &str) -> bool {
+        self.theme_cookie
+            .as_ref()
+            .is_some_and(|cookie| cookie.default == theme)
+    }
+
+    /// Check if a request asked to skip the incremental cache with the `X-Dioxus-Cache: bypass` header
+    pub(crate) fn bypasses_cache_for(&self, parts: &http::request::Parts) -> bool {
+        self.cache_bypass_header
+            && parts
+                .headers
+                .get(CACHE_BYPASS_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.trim().eq_ignore_ascii_case("bypass"))
+    }
+
+    /// Check if a path is served as static html. See [`ServeConfigBuilder::static_page`]
+    pub(crate) fn is_static_page(&self, path: &str) -> bool {
+        self.static_page_prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()))
+    }
+
+    /// Check if a request is for a static page or from a crawler that should get the page without hydration data
+    pub(crate) fn strips_hydration_for(&self, parts: &http::request::Parts) -> bool {
+        if self.is_static_page(parts.uri.path()) {
+            return true;
+        }
+        if self.crawler_user_agents.is_empty() {
+            return false;
+        }
+        let Some(user_agent) = parts
+            .headers
+            .get(http::header::USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+        else {
+            return false;
+        };
+        let user_agent = user_agent.to_ascii_lowercase();
+        self.crawler_user_agents
+            .iter()
+            .any(|crawler| user_agent.contains(crawler.as_str()))
+    }
+}
+
+/// Themes end up in attributes and class names, so only simple names are accepted
+fn is_valid_theme(theme: &str) -> bool {
+    !theme.is_empty()
+        && theme.len() <= 64
+        && theme
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+/// Get the language part of a locale, for example `en` for `en-US`
+fn primary_language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+/// The ES modules of the client bundle and their static imports. See [`ServeConfigBuilder::module_manifest`].
+///
+/// Only modules that are listed in the manifest are preloaded. Imports of a module that are not listed themselves are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct ModuleManifest {
+    entries: Vec<String>,
+    modules: Vec<(String, Vec<String>)>,
+}
+
+impl ModuleManifest {
+    /// Create a manifest for the modules that start hydration
+    pub fn new(entries: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            entries: entries.into_iter().map(Into::into).collect(),
+            modules: Vec::new(),
+        }
+    }
+
+    /// Add a module with the url it is served from and the urls of the modules it imports
+    pub fn module(
+        mut self,
+        url: impl Into<String>,
+        imports: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.modules
+            .push((url.into(), imports.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Get every module that is reachable from the entries in the order the browser discovers them, without duplicates
+    fn preloads(&self) -> Vec<String> {
+        let mut preloads: Vec<String> = Vec::new();
+        let mut queue: std::collections::VecDeque<&String> = self.entries.iter().collect();
+        while let Some(url) = queue.pop_front() {
+            if preloads.contains(url) {
+                continue;
+            }
+            let Some((_, imports)) = self.modules.iter().find(|(module, _)| module == url) else {
+                continue;
+            };
+            preloads.push(url.clone());
+            queue.extend(imports);
+        }
+        preloads
+    }
+}
+
+/// A problem with a [`ServeConfig`] found by [`ServeConfig::validate`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigProblem {
+    /// The index.html has no `<html>` tag, so the attributes the option sets can't be added
+    MissingHtmlTag {
+        /// The option that adds attributes to the `<html>` tag
+        option: &'static str,
+    },
+    /// A local asset the page links to is missing from the public directory
+    MissingAsset {
+        /// The url of the asset in the page
+        url: String,
+        /// The path the asset was expected at
+        path: PathBuf,
+    },
+    /// Two options are set that can't be used together
+    Conflict {
+        /// The first option
+        option: &'static str,
+        /// The option that conflicts with it
+        other: &'static str,
+    },
+    /// An option has a value that doesn't work
+    Invalid {
+        /// The option with the invalid value
+        option: &'static str,
+        /// Why the value doesn't work
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingHtmlTag { option } => write!(
+                f,
+                "`{option}` is set, but index.html has no <html> tag to add attributes to"
+            ),
+            Self::MissingAsset { url, path } => {
+                write!(
+                    f,
+                    "{url} is linked in the page, but {path:?} does not exist"
+                )
+            }
+            Self::Conflict { option, other } => {
+                write!(f, "`{option}` can't be used together with {other}")
+            }
+            Self::Invalid { option, reason } => write!(f, "`{option}` is invalid: {reason}"),
+        }
+    }
+}
+
+/// The browser features a page is allowed to use, sent in the `Permissions-Policy` header. See [`ServeConfigBuilder::permissions_policy`]
+///
+/// ```rust
+/// # use dioxus_fullstack::PermissionsPolicy;
+/// let policy = PermissionsPolicy::new()
+///     .deny("camera")
+///     .allow("payment", ["self", "https://pay.example.com"]);
+/// assert_eq!(policy.to_string(), r#"camera=(), payment=(self "https://pay.example.com")"#);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PermissionsPolicy {
+    features: Vec<(String, Vec<String>)>,
+}
+
+impl PermissionsPolicy {
+    /// Create a policy that doesn't restrict any features
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disable a feature for the page and every iframe in it
+    pub fn deny(mut self, feature: impl Into<String>) -> Self {
+        let feature = feature.into();
+        self.features.retain(|(existing, _)| *existing != feature);
+        self.features.push((feature, Vec::new()));
+        self
+    }
+
+    /// Allow a feature for some origins. Each origin is either `"self"` for the origin of the page, `"*"` for every origin or an
+    /// origin like `"https://example.com"`. Origins are added to the ones that are already allowed.
+    pub fn allow(
+        mut self,
+        feature: impl Into<String>,
+        origins: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let feature = feature.into();
+        let index = match self
+            .features
+            .iter()
+            .position(|(existing, _)| *existing == feature)
+        {
+            Some(index) => index,
+            None => {
+                self.features.push((feature, Vec::new()));
+                self.features.len() - 1
+            }
+        };
+        let allowed = &mut self.features[index].1;
+        for origin in origins {
+            let origin = origin.into();
+            if !allowed.contains(&origin) {
+                allowed.push(origin);
+            }
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for PermissionsPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (feature, origins)) in self.features.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{feature}=(")?;
+            for (i, origin) in origins.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" ")?;
+                }
+                match origin.as_str() {
+                    "self" | "*" => f.write_str(origin)?,
+                    origin => write!(f, "\"{origin}\"")?,
+                }
+            }
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// How long a streamed page can take to finish. See [`ServeConfigBuilder::stream_timeouts`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StreamTimeouts {
+    pub(crate) total: Option<std::time::Duration>,
+    pub(crate) idle: Option<std::time::Duration>,
+}
+
+impl StreamTimeouts {
+    /// Create timeouts that never end a stream
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// End the stream if it is still running `total` after the first chunk was sent
+    pub fn total(mut self, total: std::time::Duration) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// End the stream if no chunk was sent for `idle`
+    pub fn idle(mut self, idle: std::time::Duration) -> Self {
+        self.idle = Some(idle);
+        self
+    }
+}
+
+/// What happens when the app renders no content. See [`ServeConfigBuilder::empty_render`].
+///
+/// A page with pending suspense boundaries is only checked if it is rendered for a crawler with [`ServeConfigBuilder::crawler_strip_hydration`],
+/// because the content of streamed pages is not known before the first chunk is sent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum EmptyRenderPolicy {
+    /// Send the empty page like any other page
+    #[default]
+    Allow,
+    /// Log a warning and send the empty page
+    Warn,
+    /// Fail the render. This responds with `500 Internal Server Error` unless another render strategy succeeds
+    Error,
+    /// Send this html document instead of the empty page
+    Placeholder(String),
+}
+
+impl EmptyRenderPolicy {
+    /// Check if the policy sends something other than the rendered page
+    pub(crate) fn replaces_page(&self) -> bool {
+        matches!(self, Self::Error | Self::Placeholder(_))
+    }
+}
+
+/// How much of the rendered html the server embeds hydration checksums for. See [`ServeConfigBuilder::hydration_check`].
+///
+/// The checksum is the 32 bit FNV-1a hash of the lowercase tag names of every element in document order, each followed by a `,`.
+/// Text, comments and attributes are not part of the checksum. The client can compute the same checksum from the elements
+/// it is about to hydrate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HydrationCheck {
+    /// Don't embed any checksums
+    #[default]
+    Off,
+    /// Embed one checksum for the initial html of the page in a `<script type="application/json" id="dioxus-hydration-check">` element.
+    /// The checksum is computed before any suspense boundaries are resolved, so it covers the loading placeholders.
+    Document,
+    /// Embed the document checksum and a checksum for each suspense boundary as it is resolved. The checksum for a suspense
+    /// boundary is written in a `<script type="application/json" data-dioxus-hydration-check="{id}">` element after the html of
+    /// the boundary, where `{id}` is the same id that is passed to `window.dx_hydrate`.
+    SuspenseBoundary,
+}
+
+/// What client code that reads the hydration checksums should do when the structure it hydrates does not match the checksum from
+/// the server. The policy is only embedded in the page as the `policy` field of the checksum. The server and the built-in web
+/// renderer don't act on it. See [`ServeConfigBuilder::hydration_mismatch_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HydrationMismatchPolicy {
+    /// Hint that the client should log a warning and continue hydrating
+    #[default]
+    Warn,
+    /// Hint that the client should throw away the server rendered html and render the mismatched section again
+    Rerender,
+    /// Hint that the client should fail hydration with an error
+    Error,
+}
+
+impl HydrationMismatchPolicy {
+    /// Get the name of the policy that is embedded in the page
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Rerender => "recover-by-rerender",
+            Self::Error => "error",
+        }
+    }
 }
@@ -0,0 +1,161 @@
+//! Resolve the IP address of the client behind reverse proxies. See [`ServeConfigBuilder::client_ip_source`](crate::prelude::ServeConfigBuilder::client_ip_source)
+
+use axum::extract::ConnectInfo;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+/// The headers and proxies that are trusted to report the address of the client. See
+/// [`ServeConfigBuilder::client_ip_source`](crate::prelude::ServeConfigBuilder::client_ip_source)
+#[derive(Clone, Debug, Default)]
+pub struct ClientIpSource {
+    headers: Vec<http::HeaderName>,
+    proxies: Vec<IpCidr>,
+}
+
+impl ClientIpSource {
+    /// Create a source that only trusts the socket address
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the client address from `header` if the request came from a trusted proxy. Headers are checked in the order they are
+    /// added and the first header that contains a valid address is used.
+    ///
+    /// If the header contains a list of addresses like `X-Forwarded-For`, the last address that is not a trusted proxy is used.
+    pub fn trusted_header(mut self, header: http::HeaderName) -> Self {
+        self.headers.push(header);
+        self
+    }
+
+    /// Trust the proxies in a network to set the client address headers
+    pub fn trusted_proxy(mut self, network: IpCidr) -> Self {
+        self.proxies.push(network);
+        self
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.proxies.iter().any(|network| network.contains(ip))
+    }
+
+    /// Resolve the client address of a request
+    pub(crate) fn resolve(&self, parts: &http::request::Parts) -> Option<IpAddr> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())?;
+        if !self.is_trusted(peer) {
+            return Some(peer);
+        }
+
+        for header in &self.headers {
+            let addresses: Vec<IpAddr> = parts
+                .headers
+                .get_all(header)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .filter_map(parse_ip)
+                .collect();
+            // Walk back through the proxies that appended to the list until we find an address we don't trust
+            let client = addresses
+                .iter()
+                .rev()
+                .find(|ip| !self.is_trusted(**ip))
+                .or(addresses.first());
+            if let Some(client) = client {
+                return Some(*client);
+            }
+        }
+
+        Some(peer)
+    }
+}
+
+/// Parse an address from a header value. Ports and the brackets around IPv6 addresses are ignored
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    value
+        .parse()
+        .ok()
+        .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| {
+            value
+                .strip_prefix('[')
+                .and_then(|value| value.strip_suffix(']'))
+                .and_then(|value| value.parse().ok())
+        })
+}
+
+/// A network of IP addresses in CIDR notation like `10.0.0.0/8` or `fd00::/8`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Check if an address is in the network
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+                .to_ipv4_mapped()
+                .is_some_and(|ip| self.contains(IpAddr::V4(ip))),
+            (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+}
+
+fn prefix_matches(network: &[u8], ip: &[u8], prefix_len: u8) -> bool {
+    let full_bytes = prefix_len as usize / 8;
+    let remaining_bits = prefix_len % 8;
+    if network[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+    if remaining_bits == 0 {
+        return true;
+    }
+    let mask = 0xffu8 << (8 - remaining_bits);
+    network[full_bytes] & mask == ip[full_bytes] & mask
+}
+
+impl FromStr for IpCidr {
+    type Err = InvalidIpCidr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (network, prefix_len) = match s.split_once('/') {
+            Some((network, prefix_len)) => (network, Some(prefix_len)),
+            None => (s, None),
+        };
+        let network: IpAddr = network.parse().map_err(|_| InvalidIpCidr)?;
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len.parse().map_err(|_| InvalidIpCidr)?,
+            None => max_prefix_len,
+        };
+        if prefix_len > max_prefix_len {
+            return Err(InvalidIpCidr);
+        }
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// An error that occurs when parsing an [`IpCidr`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidIpCidr;
+
+impl std::fmt::Display for InvalidIpCidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid IP network. Expected an address with an optional prefix length like `10.0.0.0/8`")
+    }
+}
+
+impl std::error::Error for InvalidIpCidr {}
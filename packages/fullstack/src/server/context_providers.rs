@@ -0,0 +1,155 @@
+//! Compose `ContextProviders` from providers contributed by multiple modules
+
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+
+use super::ContextProviders;
+
+type BoxedProvider = Box<dyn Fn() -> Box<dyn Any> + Send + Sync + 'static>;
+
+struct Provider {
+    type_id: TypeId,
+    type_name: &'static str,
+    factory: BoxedProvider,
+}
+
+/// A builder for `ContextProviders` that feature modules can add providers to. Once every module added its providers,
+/// the builder creates the same `ContextProviders` that [`ServeConfigBuilder::context_providers`](crate::prelude::ServeConfigBuilder::context_providers)
+/// and [`DioxusRouterExt::register_server_functions_with_context`](crate::prelude::DioxusRouterExt::register_server_functions_with_context) accept.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_fullstack::prelude::*;
+/// #[derive(Clone)]
+/// struct Database;
+/// #[derive(Clone)]
+/// struct Mailer;
+///
+/// // Each module adds its own providers
+/// fn database_module(providers: ContextProvidersBuilder) -> ContextProvidersBuilder {
+///     providers.provide(|| Database)
+/// }
+///
+/// fn mail_module(providers: ContextProvidersBuilder) -> ContextProvidersBuilder {
+///     providers.provide(|| Mailer)
+/// }
+///
+/// let providers = mail_module(database_module(ContextProvidersBuilder::new()));
+/// // Fail if two modules provide the same type
+/// let providers = providers.try_build().unwrap();
+/// let cfg = ServeConfigBuilder::new().context_providers(providers);
+/// ```
+#[derive(Default)]
+pub struct ContextProvidersBuilder {
+    providers: Vec<Provider>,
+}
+
+impl ContextProvidersBuilder {
+    /// Create a new empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a provider that creates a value of type `T` for each render and server function call
+    pub fn provide<T: 'static>(mut self, factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        self.providers.push(Provider {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            factory: Box::new(move || Box::new(factory()) as Box<dyn Any>),
+        });
+        self
+    }
+
+    /// Add a type erased provider. The provider is called once to find the type it provides.
+    pub fn provide_boxed(mut self, factory: BoxedProvider) -> Self {
+        self.providers.push(Provider {
+            type_id: (*factory()).type_id(),
+            type_name: "<boxed provider>",
+            factory,
+        });
+        self
+    }
+
+    /// Add all providers from another builder after the providers in this builder
+    pub fn merge(mut self, other: Self) -> Self {
+        self.providers.extend(other.providers);
+        self
+    }
+
+    /// Get the names of all types that are provided more than once
+    pub fn conflicts(&self) -> Vec<&'static str> {
+        let mut conflicts = Vec::new();
+        for (index, provider) in self.providers.iter().enumerate() {
+            let first = self
+                .providers
+                .iter()
+                .position(|other| other.type_id == provider.type_id);
+            if first != Some(index) && !conflicts.contains(&provider.type_name) {
+                conflicts.push(provider.type_name);
+            }
+        }
+        conflicts
+    }
+
+    /// Build the `ContextProviders`. Returns an error if any type is provided more than once.
+    pub fn try_build(self) -> Result<ContextProviders, ContextProviderConflicts> {
+        let conflicts = self.conflicts();
+        if !conflicts.is_empty() {
+            return Err(ContextProviderConflicts(conflicts));
+        }
+        Ok(self.build())
+    }
+
+    /// Build the `ContextProviders`. If a type is provided more than once, the provider that was added last is used.
+    pub fn build(self) -> ContextProviders {
+        let mut providers: Vec<Provider> = Vec::with_capacity(self.providers.len());
+        for provider in self.providers {
+            providers.retain(|existing| existing.type_id != provider.type_id);
+            providers.push(provider);
+        }
+        Arc::new(
+            providers
+                .into_iter()
+                .map(|provider| provider.factory)
+                .collect(),
+        )
+    }
+}
+
+/// An error that occurs when multiple providers in a [`ContextProvidersBuilder`] provide the same type
+#[derive(Debug)]
+pub struct ContextProviderConflicts(Vec<&'static str>);
+
+impl ContextProviderConflicts {
+    /// Get the names of the types that are provided more than once
+    pub fn types(&self) -> &[&'static str] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ContextProviderConflicts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Multiple context providers provide the same type: {}",
+            self.0.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ContextProviderConflicts {}
+
+#[test]
+fn later_providers_override_earlier_ones() {
+    let builder = ContextProvidersBuilder::new()
+        .provide(|| 1u32)
+        .provide(|| "hello")
+        .merge(ContextProvidersBuilder::new().provide(|| 2u32));
+    assert_eq!(builder.conflicts(), vec![std::any::type_name::<u32>()]);
+
+    let providers = builder.build();
+    assert_eq!(providers.len(), 2);
+    let values: Vec<_> = providers.iter().map(|provider| provider()).collect();
+    assert_eq!(*values[0].downcast_ref::<&str>().unwrap(), "hello");
+    assert_eq!(*values[1].downcast_ref::<u32>().unwrap(), 2);
+}
@@ -0,0 +1,119 @@
+//! Normalize the `Host` of requests before the handlers read it. See
+//! [`ServeConfigBuilder::host_normalization`](crate::prelude::ServeConfigBuilder::host_normalization)
+
+/// How the `Host` of requests is normalized. See
+/// [`ServeConfigBuilder::host_normalization`](crate::prelude::ServeConfigBuilder::host_normalization)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HostNormalization {
+    lowercase: bool,
+    strip_default_ports: bool,
+}
+
+impl Default for HostNormalization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostNormalization {
+    /// Lowercase the host and strip the default ports
+    pub fn new() -> Self {
+        Self {
+            lowercase: true,
+            strip_default_ports: true,
+        }
+    }
+
+    /// Lowercase the host. Hosts are case insensitive. (defaults to true)
+    pub fn lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+
+    /// Remove the port if it is the default port of the scheme of the request, `80` for `http` and `443` for `https`. If the scheme is
+    /// not known, which is the case for most HTTP/1 requests, both ports are removed. (defaults to true)
+    pub fn strip_default_ports(mut self, strip_default_ports: bool) -> Self {
+        self.strip_default_ports = strip_default_ports;
+        self
+    }
+
+    /// Normalize a host with an optional port
+    fn normalize(&self, host: &str, scheme: Option<&http::uri::Scheme>) -> String {
+        let mut host = match self.lowercase {
+            true => host.to_ascii_lowercase(),
+            false => host.to_string(),
+        };
+        if self.strip_default_ports {
+            let default_ports: &[&str] = match scheme.map(|scheme| scheme.as_str()) {
+                Some("http") => &["80"],
+                Some("https") => &["443"],
+                _ => &["80", "443"],
+            };
+            // Ipv6 hosts contain colons, so only look at the part after the closing bracket
+            let port_start = host
+                .rfind(':')
+                .filter(|&colon| !host[colon..].contains(']'));
+            if let Some(colon) = port_start {
+                if default_ports.contains(&&host[colon + 1..]) {
+                    host.truncate(colon);
+                }
+            }
+        }
+        host
+    }
+}
+
+/// Normalize the host of a request if a normalization is configured
+pub(crate) fn normalize_host(
+    parts: &mut http::request::Parts,
+    normalization: Option<HostNormalization>,
+) {
+    let Some(normalization) = normalization else {
+        return;
+    };
+
+    let scheme = parts.uri.scheme().cloned();
+    if let Some(host) = parts
+        .headers
+        .get(http::header::HOST)
+        .and_then(|host| host.to_str().ok())
+    {
+        let normalized = normalization.normalize(host, scheme.as_ref());
+        if normalized != host {
+            if let Ok(normalized) = http::HeaderValue::from_str(&normalized) {
+                parts.headers.insert(http::header::HOST, normalized);
+            }
+        }
+    }
+
+    // HTTP/2 requests send the host in the authority of the uri instead of the header
+    if let Some(authority) = parts.uri.authority() {
+        let normalized = normalization.normalize(authority.as_str(), scheme.as_ref());
+        if normalized != authority.as_str() {
+            let mut uri = parts.uri.clone().into_parts();
+            uri.authority = normalized.parse().ok();
+            if let Ok(normalized) = http::Uri::from_parts(uri) {
+                parts.uri = normalized;
+            }
+        }
+    }
+}
+
+#[test]
+fn strips_default_ports() {
+    let normalization = HostNormalization::new();
+    assert_eq!(
+        normalization.normalize("Example.com:443", None),
+        "example.com"
+    );
+    assert_eq!(
+        normalization.normalize("example.com:8080", None),
+        "example.com:8080"
+    );
+    assert_eq!(normalization.normalize("[::1]:80", None), "[::1]");
+    let https = http::uri::Scheme::HTTPS;
+    assert_eq!(
+        normalization.normalize("example.com:80", Some(&https)),
+        "example.com:80"
+    );
+}
@@ -60,6 +60,9 @@ pub fn launch(
                 }
             }
 
+            // Keep the config around to run the shutdown callback after the server stops
+            let shutdown_cfg = platform_config.as_ref().ok().cloned();
+
             #[allow(unused_mut)]
             let mut router =
                 axum::Router::new().serve_dioxus_application(TryIntoResult(platform_config), root);
@@ -67,8 +70,48 @@ pub fn launch(
             let router = router.into_make_service();
             let listener = tokio::net::TcpListener::bind(address).await.unwrap();
 
-            axum::serve(listener, router).await.unwrap();
+            axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown_signal())
+                .await
+                .unwrap();
+
+            if let Some(cfg) = shutdown_cfg {
+                cfg.shutdown().await;
+            }
+            std::process::exit(0);
         });
 
     unreachable!("Launching a fullstack app should never return")
 }
+
+/// Wait until the process is asked to stop with `Ctrl+C` or `SIGTERM`
+#[cfg(not(target_arch = "wasm32"))]
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {err}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("Failed to listen for SIGTERM: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("Shutting down the server");
+}
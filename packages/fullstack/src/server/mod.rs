@@ -52,7 +52,18 @@
 //! }
 //! ```
 
+mod client_ip;
+mod context_providers;
+mod host;
 pub mod launch;
+pub(crate) mod server_fn_args;
+pub(crate) mod server_fn_cache;
+pub(crate) mod server_fn_errors;
+
+pub use client_ip::{ClientIpSource, InvalidIpCidr, IpCidr};
+pub use context_providers::*;
+pub use host::HostNormalization;
+pub use server_fn_cache::{invalidate_server_fn_response, DEFAULT_SERVER_FN_CACHE_CAPACITY};
 
 #[allow(unused)]
 pub(crate) type ContextProviders =
@@ -68,7 +79,9 @@ use axum::{
 use dioxus_lib::prelude::{Element, VirtualDom};
 use http::header::*;
 
+use futures_util::StreamExt;
 use std::sync::Arc;
+use tracing::Instrument;
 
 use crate::prelude::*;
 
@@ -100,6 +113,11 @@ pub trait DioxusRouterExt<S> {
 
     /// Registers server functions with some additional context to insert into the [`DioxusServerContext`] for that handler.
     ///
+    /// The extensions of each request are available in the [`DioxusServerContext`] and on the request that is passed to the
+    /// server function and its middleware. The request that is passed to the server function gets a clone of the headers and
+    /// extensions, so keep large extension values behind an `Arc`. Read them with [`DioxusServerContext::request_parts`] or [`extract`] inside the server
+    /// function.
+    ///
     /// # Example
     /// ```rust, no_run
     /// # use dioxus_lib::prelude::*;
@@ -118,6 +136,28 @@ pub trait DioxusRouterExt<S> {
     /// ```
     fn register_server_functions_with_context(self, context_providers: ContextProviders) -> Self;
 
+    /// Registers server functions with the context providers and server function settings of a [`ServeConfig`]. Use this if you
+    /// add the [`render_handler`] to the router yourself, so server functions get the same settings as the pages.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let cfg = ServeConfig::builder().max_set_cookie_headers(16).build().unwrap();
+    ///     let router = axum::Router::new()
+    ///         .register_server_functions_with_config(&cfg)
+    ///         .fallback(axum::routing::any(render_handler).with_state(RenderHandleState::new(cfg, app)))
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_config(self, cfg: &ServeConfig) -> Self;
+
     /// Serves the static WASM for your Dioxus application (except the generated index.html).
     ///
     /// # Example
@@ -176,25 +216,12 @@ impl<S> DioxusRouterExt<S> for Router<S>
 where
     S: Send + Sync + Clone + 'static,
 {
-    fn register_server_functions_with_context(
-        mut self,
-        context_providers: ContextProviders,
-    ) -> Self {
-        use http::method::Method;
-
-        for (path, method) in server_fn::axum::server_fn_paths() {
-            tracing::trace!("Registering server function: {} {}", method, path);
-            let context_providers = context_providers.clone();
-            let handler = move |req| handle_server_fns_inner(path, context_providers, req);
-            self = match method {
-                Method::GET => self.route(path, get(handler)),
-                Method::POST => self.route(path, post(handler)),
-                Method::PUT => self.route(path, put(handler)),
-                _ => unimplemented!("Unsupported server function method: {}", method),
-            };
-        }
+    fn register_server_functions_with_context(self, context_providers: ContextProviders) -> Self {
+        register_server_fns(self, context_providers, Default::default())
+    }
 
-        self
+    fn register_server_functions_with_config(self, cfg: &ServeConfig) -> Self {
+        register_server_fns(self, cfg.context_providers.clone(), cfg.handler.clone())
     }
 
     fn serve_static_assets(mut self) -> Self {
@@ -247,21 +274,19 @@ where
         Error: std::error::Error,
     {
         let cfg = cfg.try_into();
-        let context_providers = cfg
-            .as_ref()
-            .map(|cfg| cfg.context_providers.clone())
-            .unwrap_or_default();
 
         // Add server functions and render index.html
-        let server = self
-            .serve_static_assets()
-            .register_server_functions_with_context(context_providers);
+        let server = self.serve_static_assets();
+        let server = match &cfg {
+            Ok(cfg) => server.register_server_functions_with_config(cfg),
+            Err(_) => server.register_server_functions(),
+        };
 
         match cfg {
             Ok(cfg) => {
                 let ssr_state = SSRState::new(&cfg);
                 server.fallback(
-                    get(render_handler)
+                    any(render_handler)
                         .with_state(RenderHandleState::new(cfg, app).with_ssr_state(ssr_state)),
                 )
             }
@@ -273,6 +298,33 @@ where
     }
 }
 
+/// Add a route for every server function that runs it with the context providers and settings
+fn register_server_fns<S>(
+    mut router: Router<S>,
+    context_providers: ContextProviders,
+    handler: Arc<crate::serve_config::HandlerConfig>,
+) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+{
+    use http::method::Method;
+
+    for (path, method) in server_fn::axum::server_fn_paths() {
+        tracing::trace!("Registering server function: {} {}", method, path);
+        let context_providers = context_providers.clone();
+        let handler = handler.clone();
+        let handler = move |req| handle_server_fns_inner(path, context_providers, handler, req);
+        router = match method {
+            Method::GET => router.route(path, get(handler)),
+            Method::POST => router.route(path, post(handler)),
+            Method::PUT => router.route(path, put(handler)),
+            _ => unimplemented!("Unsupported server function method: {}", method),
+        };
+    }
+
+    router
+}
+
 fn apply_request_parts_to_response<B>(
     headers: hyper::header::HeaderMap,
     response: &mut axum::response::Response<B>,
@@ -283,6 +335,201 @@ fn apply_request_parts_to_response<B>(
     }
 }
 
+/// The default maximum number of `Set-Cookie` headers a server function response can have. See
+/// [`ServeConfigBuilder::max_set_cookie_headers`]
+pub const DEFAULT_MAX_SET_COOKIE_HEADERS: usize = 64;
+
+/// The default maximum size of a form-urlencoded server function body in bytes that
+/// [`DioxusServerContext::form`](crate::prelude::DioxusServerContext::form) reads. See
+/// [`ServeConfigBuilder::max_form_body_size`](crate::prelude::ServeConfigBuilder::max_form_body_size)
+pub const DEFAULT_MAX_FORM_BODY_SIZE: usize = 1024 * 1024;
+
+/// Get the status of the response for an error that occurred while buffering a request body. Bodies that are larger than a limit
+/// are rejected with `413 Payload Too Large` and every other error, like a client that disconnected, with `400 Bad Request`
+pub(crate) fn body_error_status(err: &axum::Error) -> StatusCode {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+    // The limit error can be wrapped in the errors of the bodies around it
+    while let Some(err) = source {
+        if err.is::<http_body_util::LengthLimitError>() {
+            return StatusCode::PAYLOAD_TOO_LARGE;
+        }
+        source = err.source();
+    }
+    StatusCode::BAD_REQUEST
+}
+
+/// Check if a request has a form-urlencoded body
+pub(crate) fn is_form_urlencoded(headers: &http::HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .is_some_and(|mime| {
+            mime.trim()
+                .eq_ignore_ascii_case("application/x-www-form-urlencoded")
+        })
+}
+
+/// Allow elements with `nonce` in the `script-src` and `style-src` directives of a content security policy. If a directive is missing,
+/// it is created from the `default-src` directive. Without either directive, the type of resource is not restricted and nothing is added.
+fn add_nonce_to_policy(policy: &str, nonce: &str) -> String {
+    let source = format!("'nonce-{nonce}'");
+    add_sources_to_policy(
+        policy,
+        [
+            ("script-src", source.as_str()),
+            ("style-src", source.as_str()),
+        ],
+    )
+}
+
+/// Add each `(directive, source)` to a content security policy. Sources that the directive already allows are skipped. Missing
+/// directives are created from the `default-src` directive like in [`add_nonce_to_policy`].
+fn add_sources_to_policy<'a>(
+    policy: &str,
+    sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let mut directives: Vec<String> = policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(String::from)
+        .collect();
+    let directive_name = |directive: &str| {
+        directive
+            .split_ascii_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    let default_sources = directives
+        .iter()
+        .find(|directive| directive_name(directive) == "default-src")
+        .map(|directive| directive["default-src".len()..].to_string());
+
+    for (name, source) in sources {
+        let sources = match directives
+            .iter()
+            .position(|directive| directive_name(directive) == name)
+        {
+            Some(index) => directives.remove(index)[name.len()..].to_string(),
+            None => match &default_sources {
+                Some(sources) => sources.clone(),
+                None => continue,
+            },
+        };
+        // 'none' can't be combined with other sources
+        let mut sources: Vec<&str> = sources
+            .split_ascii_whitespace()
+            .filter(|source| !source.eq_ignore_ascii_case("'none'"))
+            .collect();
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+        directives.push(format!("{name} {}", sources.join(" ")));
+    }
+
+    directives.join("; ")
+}
+
+/// Drop any `Set-Cookie` headers past the limit set with [`ServeConfigBuilder::max_set_cookie_headers`]
+fn limit_set_cookie_headers(headers: &mut hyper::header::HeaderMap, max: usize) {
+    let count = headers.get_all(SET_COOKIE).iter().count();
+    if count <= max {
+        return;
+    }
+
+    tracing::warn!(
+        "A server function set {count} cookies, but only {max} are allowed. The extra cookies were dropped"
+    );
+    let kept: Vec<_> = headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .take(max)
+        .cloned()
+        .collect();
+    headers.remove(SET_COOKIE);
+    for cookie in kept {
+        headers.append(SET_COOKIE, cookie);
+    }
+}
+
+/// The default budget for the headers of a response in bytes. See
+/// [`ServeConfigBuilder::max_response_header_size`](crate::prelude::ServeConfigBuilder::max_response_header_size)
+pub const DEFAULT_MAX_RESPONSE_HEADER_SIZE: usize = 16 * 1024;
+
+/// Headers that only improve performance and can be dropped if the response headers grow too large, from the least important
+const OPTIONAL_HEADERS: &[HeaderName] = &[LINK];
+
+/// Drop optional headers until the headers fit in a budget of `max` bytes
+fn limit_response_headers(headers: &mut hyper::header::HeaderMap, max: usize) {
+    // Each header is sent as `name: value\r\n`
+    let header_size =
+        |name: &HeaderName, value: &http::HeaderValue| name.as_str().len() + value.len() + 4;
+    let mut size: usize = headers
+        .iter()
+        .map(|(name, value)| header_size(name, value))
+        .sum();
+    if size <= max {
+        return;
+    }
+
+    let original_size = size;
+    let mut dropped = 0;
+    for name in OPTIONAL_HEADERS {
+        let mut values: Vec<_> = headers.get_all(name).iter().cloned().collect();
+        if values.is_empty() {
+            continue;
+        }
+        // Drop the last values first
+        while size > max {
+            let Some(value) = values.pop() else {
+                break;
+            };
+            size -= header_size(name, &value);
+            dropped += 1;
+        }
+        headers.remove(name);
+        for value in values {
+            headers.append(name, value);
+        }
+        if size <= max {
+            break;
+        }
+    }
+
+    if size <= max {
+        tracing::warn!(
+            "The response headers were {original_size} bytes, but only {max} bytes are allowed. {dropped} optional headers were dropped"
+        );
+    } else {
+        tracing::warn!(
+            "The response headers are {size} bytes, but only {max} bytes are allowed. The headers that are left are required, so they were kept"
+        );
+    }
+}
+
+/// Check if a server function request will be rejected based on its headers alone
+fn reject_upload(
+    parts: &http::request::Parts,
+    max_body_size: Option<usize>,
+) -> Option<Response<Body>> {
+    // `100-continue` is the only expectation defined by HTTP
+    if let Some(expect) = parts.headers.get(EXPECT) {
+        if !expect.as_bytes().eq_ignore_ascii_case(b"100-continue") {
+            return Some(StatusCode::EXPECTATION_FAILED.into_response());
+        }
+    }
+
+    let limit = max_body_size?;
+    let content_length = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<usize>().ok())?;
+    (content_length > limit).then(|| StatusCode::PAYLOAD_TOO_LARGE.into_response())
+}
+
 fn add_server_context(server_context: &DioxusServerContext, context_providers: &ContextProviders) {
     for index in 0..context_providers.len() {
         let context_providers = context_providers.clone();
@@ -296,16 +543,193 @@ pub struct RenderHandleState {
     config: ServeConfig,
     build_virtual_dom: Arc<dyn Fn() -> VirtualDom + Send + Sync>,
     ssr_state: once_cell::sync::OnceCell<SSRState>,
+    on_response: Option<OnResponseFn>,
+    accept_exempt_prefixes: Vec<String>,
+    gone_paths: Vec<String>,
+    gone_response: GoneResponse,
+    trace_renders: Option<TraceSampling>,
+    render_strategies: Vec<RenderStrategy>,
+    on_render_outputs: Option<OnRenderOutputsFn>,
+    short_circuit: Option<ShortCircuitFn>,
+    not_acceptable_message: Option<String>,
+    methods: Vec<(http::Method, MethodBehavior)>,
+    cold_start: Option<ColdStart>,
+    head_fills_cache: bool,
+}
+
+/// A flag that marks the server as ready to render pages. See [`RenderHandleState::with_cold_start_page`]
+#[derive(Clone, Debug, Default)]
+pub struct Readiness(Arc<std::sync::atomic::AtomicBool>);
+
+impl Readiness {
+    /// Create a flag for a server that is not ready yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark the server as ready. Every clone of the flag sees the change
+    pub fn set_ready(&self) {
+        self.0.store(true, std::sync::atomic::Ordering::Release);
+    }
+
+    /// Check if the server is ready
+    pub fn is_ready(&self) -> bool {
+        self.0.load(std::sync::atomic::Ordering::Acquire)
+    }
 }
 
+/// The page that is served until the server is ready
+#[derive(Clone)]
+struct ColdStart {
+    readiness: Readiness,
+    html: Arc<str>,
+    retry_after: std::time::Duration,
+}
+
+/// How [`render_handler`] responds to a request method. See [`RenderHandleState::with_method`]
+#[derive(Clone)]
+pub enum MethodBehavior {
+    /// Render the page
+    Render,
+    /// Respond with `405 Method Not Allowed` and an `Allow` header that lists the methods that are not rejected
+    NotAllowed,
+    /// Run a custom handler. See [`MethodBehavior::custom`]
+    Custom(MethodHandler),
+    /// Run the server function a form without javascript posted to with an action from [`form_action`], then render the page like
+    /// a `GET` request. Cookies the server function sets are sent with the page. If the server function fails or redirects, its
+    /// response is sent instead of the page.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_method(http::Method::POST, MethodBehavior::ServerFnForm);
+    /// ```
+    ServerFnForm,
+}
+
+impl MethodBehavior {
+    /// Run `handler` with the whole request, including the body. If the handler returns a response, it is sent as is. If it returns
+    /// `None`, the page is rendered like a `GET` request. This lets a page process a form post and then render the result.
+    pub fn custom<F, Fut>(handler: F) -> Self
+    where
+        F: Fn(Request<Body>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Option<Response<Body>>> + Send + 'static,
+    {
+        Self::Custom(MethodHandler(Arc::new(move |request| {
+            Box::pin(handler(request))
+                as std::pin::Pin<Box<dyn std::future::Future<Output = _> + Send>>
+        })))
+    }
+}
+
+/// A custom handler for a request method. Create one with [`MethodBehavior::custom`]
+#[derive(Clone)]
+pub struct MethodHandler(
+    Arc<
+        dyn Fn(
+                Request<Body>,
+            ) -> std::pin::Pin<
+                Box<dyn std::future::Future<Output = Option<Response<Body>>> + Send>,
+            > + Send
+            + Sync,
+    >,
+);
+
+/// The response sent for paths that are marked as gone with [`RenderHandleState::with_gone_path`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GoneResponse {
+    /// Respond with `410 Gone` and an empty body without rendering the app
+    #[default]
+    Empty,
+    /// Render the app for the path like normal and send the page with a `410 Gone` status. This lets your app show an explanatory page
+    Render,
+}
+
+/// A way to render a page. See [`RenderHandleState::with_render_strategies`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderStrategy {
+    /// Stream the page to the client as suspense boundaries resolve. Once the first chunk of html is sent, later failures are
+    /// sent in the stream instead of falling back to the next strategy.
+    #[default]
+    Streaming,
+    /// Render the whole page before sending anything. This is slower, but any failure during the render can fall back to the next strategy.
+    Buffered,
+}
+
+/// Which renders get a detailed tracing span. See [`RenderHandleState::with_trace_renders`]
+#[derive(Clone)]
+pub enum TraceSampling {
+    /// Trace a fraction of renders. `0.0` traces no renders and `1.0` traces every render
+    Ratio(f64),
+    /// Trace the renders the function returns `true` for
+    Filter(Arc<dyn Fn(&http::request::Parts) -> bool + Send + Sync>),
+}
+
+impl TraceSampling {
+    /// Trace the renders the function returns `true` for
+    pub fn filter(filter: impl Fn(&http::request::Parts) -> bool + Send + Sync + 'static) -> Self {
+        Self::Filter(Arc::new(filter))
+    }
+}
+
+/// Information about how a page was rendered. [`render_handler`] adds this to the extensions of every
+/// successful response which makes it possible to check how a response was created in tests.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # fn app() -> Element { todo!() }
+/// use tower::ServiceExt;
+///
+/// # async fn test() {
+/// let cfg = ServeConfig::builder()
+///     .incremental(IncrementalRendererConfig::default())
+///     .build()
+///     .unwrap();
+/// let router = axum::Router::new().serve_dioxus_application(cfg, app);
+/// let request = || {
+///     http::Request::get("/")
+///         .header("accept", "text/html")
+///         .body(axum::body::Body::empty())
+///         .unwrap()
+/// };
+///
+/// let first = router.clone().oneshot(request()).await.unwrap();
+/// assert!(!first.extensions().get::<RenderInfo>().unwrap().cache_hit);
+/// // Read the body so the render finishes and is added to the cache
+/// axum::body::to_bytes(first.into_body(), usize::MAX).await.unwrap();
+///
+/// let second = router.oneshot(request()).await.unwrap();
+/// let info = second.extensions().get::<RenderInfo>().unwrap();
+/// assert!(info.cache_hit);
+/// assert_eq!(info.strategy, RenderStrategy::Streaming);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RenderInfo {
+    /// If the response was served from the incremental cache instead of rendering the app
+    pub cache_hit: bool,
+    /// The strategy that produced the response. If an earlier strategy in [`RenderHandleState::with_render_strategies`] failed,
+    /// this is the strategy the render fell back to.
+    pub strategy: RenderStrategy,
+}
+
+/// A callback that is run with the request parts, the final response and the total duration of every render
+type OnResponseFn =
+    Arc<dyn Fn(&http::request::Parts, &Response<Body>, std::time::Duration) + Send + Sync>;
+
+/// A callback that reads the outputs of a render and updates the response
+type OnRenderOutputsFn = Arc<dyn Fn(&RenderOutputs, &mut Response<Body>) + Send + Sync>;
+
+/// A callback that can respond to a request before the render handler does anything else
+type ShortCircuitFn = Arc<dyn Fn(&http::request::Parts) -> Option<Response<Body>> + Send + Sync>;
+
 impl RenderHandleState {
     /// Create a new [`RenderHandleState`]
     pub fn new(config: ServeConfig, root: fn() -> Element) -> Self {
-        Self {
-            config,
-            build_virtual_dom: Arc::new(move || VirtualDom::new(root)),
-            ssr_state: Default::default(),
-        }
+        Self::new_with_virtual_dom_factory(config, move || VirtualDom::new(root))
     }
 
     /// Create a new [`RenderHandleState`] with a custom [`VirtualDom`] factory. This method can be used to pass context into the root component of your application.
@@ -317,6 +741,21 @@ impl RenderHandleState {
             config,
             build_virtual_dom: Arc::new(build_virtual_dom),
             ssr_state: Default::default(),
+            on_response: None,
+            accept_exempt_prefixes: Vec::new(),
+            gone_paths: Vec::new(),
+            gone_response: GoneResponse::default(),
+            trace_renders: None,
+            render_strategies: vec![RenderStrategy::Streaming],
+            on_render_outputs: None,
+            short_circuit: None,
+            not_acceptable_message: None,
+            methods: vec![
+                (http::Method::GET, MethodBehavior::Render),
+                (http::Method::HEAD, MethodBehavior::Render),
+            ],
+            cold_start: None,
+            head_fills_cache: true,
         }
     }
 
@@ -335,9 +774,640 @@ impl RenderHandleState {
         self
     }
 
+    /// Set a callback that is run after every render with the request parts, the final response and the time it took to create the response.
+    /// The callback runs for both successful and error responses which makes it a good place for access logs and metrics.
+    ///
+    /// When the page is streamed, the duration is measured until the response headers are ready, not until the body finishes streaming.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_on_response(
+    ///     |parts, response, duration| {
+    ///         println!("{} {} {} in {:?}", parts.method, parts.uri, response.status(), duration);
+    ///     },
+    /// );
+    /// ```
+    pub fn with_on_response(
+        mut self,
+        on_response: impl Fn(&http::request::Parts, &Response<Body>, std::time::Duration)
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.on_response = Some(Arc::new(on_response));
+        self
+    }
+
+    /// Always render HTML for paths that start with `prefix`, even if the request does not accept `text/html`. Requests to other paths that
+    /// don't accept `text/html` are still rejected with `406 Not Acceptable`.
+    ///
+    /// This is useful for endpoints that are consumed by tools which don't send an `Accept` header with `text/html`.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_accept_exempt_prefix("/internal/status");
+    /// ```
+    pub fn with_accept_exempt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.accept_exempt_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Serve `html` with `503 Service Unavailable` and a `Retry-After` header until `readiness` is set. Set the flag once the caches
+    /// are warm so a rolling deploy doesn't send real traffic to a cold server. The page is served from memory without rendering.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let readiness = Readiness::new();
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_cold_start_page(
+    ///     readiness.clone(),
+    ///     "<!DOCTYPE html><html><body>Starting up, try again in a few seconds</body></html>",
+    ///     std::time::Duration::from_secs(5),
+    /// );
+    ///
+    /// // Once prewarming is done
+    /// readiness.set_ready();
+    /// ```
+    pub fn with_cold_start_page(
+        mut self,
+        readiness: Readiness,
+        html: impl Into<String>,
+        retry_after: std::time::Duration,
+    ) -> Self {
+        self.cold_start = Some(ColdStart {
+            readiness,
+            html: html.into().into(),
+            retry_after,
+        });
+        self
+    }
+
+    /// Create the response for the cold start page if the server is not ready yet
+    fn cold_start_response(&self) -> Option<Response<Body>> {
+        let cold_start = self
+            .cold_start
+            .as_ref()
+            .filter(|cold_start| !cold_start.readiness.is_ready())?;
+        let mut response = (
+            StatusCode::SERVICE_UNAVAILABLE,
+            axum::response::Html(cold_start.html.to_string()),
+        )
+            .into_response();
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, cold_start.retry_after.as_secs().into());
+        response
+            .headers_mut()
+            .insert(CACHE_CONTROL, http::HeaderValue::from_static("no-store"));
+        Some(response)
+    }
+
+    /// Set how the handler responds to requests with `method`. By default, `GET` and `HEAD` requests are rendered and every other
+    /// method is rejected with `405 Method Not Allowed`.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// // Process the form posts of pages without javascript, then render the page
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_method(
+    ///     http::Method::POST,
+    ///     MethodBehavior::custom(|request| async move {
+    ///         let _form = axum::body::to_bytes(request.into_body(), 1024 * 1024).await;
+    ///         None
+    ///     }),
+    /// );
+    /// ```
+    pub fn with_method(mut self, method: http::Method, behavior: MethodBehavior) -> Self {
+        self.methods.retain(|(existing, _)| *existing != method);
+        self.methods.push((method, behavior));
+        self
+    }
+
+    /// Set if `HEAD` requests that miss the incremental cache finish the render in the background to fill the cache. `HEAD` and
+    /// `GET` requests for the same URL share one cache entry, so either method can fill the entry the other reads. `HEAD` responses
+    /// always have the headers of the `GET` response without the body. (defaults to true)
+    ///
+    /// If this is disabled, the render of a `HEAD` request that misses the cache is cancelled as soon as the headers are ready.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// // Health checks send HEAD requests. Don't spend time rendering pages for them
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_head_fills_cache(false);
+    /// ```
+    pub fn with_head_fills_cache(mut self, head_fills_cache: bool) -> Self {
+        self.head_fills_cache = head_fills_cache;
+        self
+    }
+
+    /// Run the server function a form posted to for [`MethodBehavior::ServerFnForm`] and turn the request into a `GET` request
+    /// for the page. Returns the cookies the server function set, or the response to send instead of the page.
+    async fn run_form_server_fn(
+        &self,
+        parts: &mut http::request::Parts,
+        body: Body,
+    ) -> Result<Vec<http::HeaderValue>, Response<Body>> {
+        let Some((path, page)) = crate::forms::split_form_action(&parts.uri) else {
+            return Err(StatusCode::BAD_REQUEST.into_response());
+        };
+        let Some(path) = server_fn::axum::server_fn_paths()
+            .find(|(server_fn, method)| *server_fn == path && *method == http::Method::POST)
+            .map(|(server_fn, _)| server_fn)
+        else {
+            return Err(StatusCode::NOT_FOUND.into_response());
+        };
+
+        let mut request = Request::new(body);
+        *request.method_mut() = http::Method::POST;
+        *request.uri_mut() = http::Uri::from_static(path);
+        *request.headers_mut() = parts.headers.clone();
+        // Get the response of the server function instead of a redirect to the referrer
+        request.headers_mut().remove(ACCEPT);
+        let response = handle_server_fns_inner(
+            path,
+            self.config.context_providers.clone(),
+            self.config.handler.clone(),
+            request,
+        )
+        .await
+        .into_response();
+        if !response.status().is_success() || response.headers().contains_key(LOCATION) {
+            return Err(response);
+        }
+
+        parts.method = http::Method::GET;
+        parts.uri = page;
+        parts.headers.remove(CONTENT_TYPE);
+        parts.headers.remove(CONTENT_LENGTH);
+        Ok(response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .cloned()
+            .collect())
+    }
+
+    /// Create the `405 Method Not Allowed` response with the methods the handler accepts
+    fn method_not_allowed(&self) -> Response<Body> {
+        let allow = self
+            .methods
+            .iter()
+            .filter(|(_, behavior)| !matches!(behavior, MethodBehavior::NotAllowed))
+            .map(|(method, _)| method.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut response = StatusCode::METHOD_NOT_ALLOWED.into_response();
+        if let Ok(allow) = http::HeaderValue::from_str(&allow) {
+            response.headers_mut().insert(ALLOW, allow);
+        }
+        response
+    }
+
+    /// Explain why requests that don't accept `text/html` are rejected with `406 Not Acceptable`. The message is sent as
+    /// `{"error": message}` if the request accepts JSON and as plain text otherwise. By default, the response has an empty body.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_not_acceptable_message("This path only serves HTML pages. Use /api for JSON");
+    /// ```
+    pub fn with_not_acceptable_message(mut self, message: impl Into<String>) -> Self {
+        self.not_acceptable_message = Some(message.into());
+        self
+    }
+
+    /// Create the response for a request that doesn't accept `text/html`
+    fn not_acceptable(&self, accepts: &str) -> Response<Body> {
+        let Some(message) = &self.not_acceptable_message else {
+            return StatusCode::NOT_ACCEPTABLE.into_response();
+        };
+        let accepts_json = accepts
+            .split(',')
+            .filter_map(|mime| mime.split(';').next())
+            .map(str::trim)
+            .any(|mime| mime == "application/json" || mime.ends_with("+json"));
+        if accepts_json {
+            (
+                StatusCode::NOT_ACCEPTABLE,
+                axum::Json(serde_json::json!({ "error": message })),
+            )
+                .into_response()
+        } else {
+            (StatusCode::NOT_ACCEPTABLE, message.clone()).into_response()
+        }
+    }
+
+    /// Mark a path as permanently removed. Requests to the path are answered with `410 Gone` which tells search engines to drop the page
+    /// faster than a `404 Not Found`. If the path ends with `*`, every path that starts with the rest of the pattern is marked as gone.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_gone_path("/old-pricing")
+    ///     .with_gone_path("/legacy/*")
+    ///     .with_gone_response(GoneResponse::Render);
+    /// ```
+    pub fn with_gone_path(mut self, path: impl Into<String>) -> Self {
+        self.gone_paths.push(path.into());
+        self
+    }
+
+    /// Set what is sent for paths marked as gone with [`Self::with_gone_path`]. (defaults to [`GoneResponse::Empty`])
+    pub fn with_gone_response(mut self, gone_response: GoneResponse) -> Self {
+        self.gone_response = gone_response;
+        self
+    }
+
+    /// Wrap renders in a `render` tracing span. Creating a span for every request is expensive at high load, so only the renders
+    /// selected by `sampling` get a span. Renders that fail with a server error are always traced.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// // Trace one in every hundred renders
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_trace_renders(TraceSampling::Ratio(0.01));
+    ///
+    /// // Or trace every render with a debug header
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_trace_renders(
+    ///     TraceSampling::filter(|parts| parts.headers.contains_key("x-debug-trace")),
+    /// );
+    /// ```
+    pub fn with_trace_renders(mut self, sampling: TraceSampling) -> Self {
+        self.trace_renders = Some(sampling);
+        self
+    }
+
+    /// Set the strategies that are tried in order until one renders the page successfully. Each attempt renders a fresh [`VirtualDom`]
+    /// and each failure is logged. (defaults to `[RenderStrategy::Streaming]`)
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// // If streaming fails, fall back to rendering the whole page before sending it
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_render_strategies([RenderStrategy::Streaming, RenderStrategy::Buffered]);
+    /// ```
+    pub fn with_render_strategies(
+        mut self,
+        strategies: impl IntoIterator<Item = RenderStrategy>,
+    ) -> Self {
+        self.render_strategies = strategies.into_iter().collect();
+        self
+    }
+
+    /// Set a callback that reads the values components wrote with [`DioxusServerContext::set_render_output`] and updates the response.
+    /// The callback runs after every successful render before the response is sent. See [`DioxusServerContext::set_render_output`] for
+    /// which outputs are observable when the page is streamed.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// #[derive(Clone)]
+    /// struct RenderedProduct(usize);
+    ///
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_render_outputs(
+    ///     |outputs, response| {
+    ///         if let Some(RenderedProduct(id)) = outputs.get::<RenderedProduct>() {
+    ///             response.headers_mut().insert("x-product-id", (*id).into());
+    ///         }
+    ///     },
+    /// );
+    /// ```
+    pub fn with_render_outputs(
+        mut self,
+        on_render_outputs: impl Fn(&RenderOutputs, &mut Response<Body>) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_render_outputs = Some(Arc::new(on_render_outputs));
+        self
+    }
+
+    /// Set a callback that runs first for every request to the render handler. If the callback returns a response, it is sent as is and
+    /// nothing else in the handler runs. The request is not rendered, traced or passed to [`Self::with_on_response`].
+    ///
+    /// This is an escape hatch for edge cases like custom redirects, health checks or setting A/B bucketing cookies.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// use axum::response::{IntoResponse, Redirect};
+    ///
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_short_circuit(
+    ///     |parts| {
+    ///         (parts.uri.path() == "/old-blog").then(|| Redirect::permanent("/blog").into_response())
+    ///     },
+    /// );
+    /// ```
+    pub fn with_short_circuit(
+        mut self,
+        short_circuit: impl Fn(&http::request::Parts) -> Option<Response<Body>> + Send + Sync + 'static,
+    ) -> Self {
+        self.short_circuit = Some(Arc::new(short_circuit));
+        self
+    }
+
+    /// Check if the render for a request should be traced
+    fn is_trace_sampled(&self, parts: &http::request::Parts) -> bool {
+        match &self.trace_renders {
+            None => false,
+            Some(TraceSampling::Ratio(ratio)) if *ratio >= 1.0 => true,
+            Some(TraceSampling::Ratio(ratio)) if *ratio <= 0.0 => false,
+            Some(TraceSampling::Ratio(ratio)) => {
+                let mut bytes = [0; 8];
+                self.config.rand_source().fill_bytes(&mut bytes);
+                (u64::from_le_bytes(bytes) as f64 / u64::MAX as f64) < *ratio
+            }
+            Some(TraceSampling::Filter(filter)) => filter(parts),
+        }
+    }
+
+    /// Check if a path was marked as gone
+    fn is_gone(&self, path: &str) -> bool {
+        self.gone_paths
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => path == pattern,
+            })
+    }
+
+    /// Check if a path is exempt from the `Accept` check
+    fn is_accept_exempt(&self, path: &str) -> bool {
+        self.accept_exempt_prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()))
+    }
+
     fn ssr_state(&self) -> &SSRState {
         self.ssr_state.get_or_init(|| SSRState::new(&self.config))
     }
+
+    /// Render the page for a request into a response
+    async fn respond(
+        &self,
+        parts: Arc<parking_lot::RwLock<http::request::Parts>>,
+    ) -> Response<Body> {
+        // Retired paths short circuit before rendering
+        let gone = self.is_gone(parts.read().uri.path());
+        if gone && self.gone_response == GoneResponse::Empty {
+            return StatusCode::GONE.into_response();
+        }
+
+        // Only respond to requests for HTML unless the path is exempt
+        let accept_exempt = self.is_accept_exempt(parts.read().uri.path())
+            || self.config.is_static_page(parts.read().uri.path());
+        if !accept_exempt {
+            if let Some(mime) = parts.read().headers.get(ACCEPT) {
+                let mime = mime.to_str().map(|mime| mime.to_ascii_lowercase());
+                match mime {
+                    Ok(accepts) if accepts.contains("text/html") => {}
+                    Ok(accepts) => return self.not_acceptable(&accepts),
+                    Err(_) => return self.not_acceptable(""),
+                }
+            }
+        }
+
+        let cfg = &self.config;
+
+        let url = parts
+            .read()
+            .uri
+            .path_and_query()
+            .map(|path_and_query| path_and_query.to_string());
+        let Some(url) = url else {
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        // Create the server context with info from the request
+        let server_context = DioxusServerContext::from_shared_parts(parts.clone());
+        // Provide additional context from the render state
+        add_server_context(&server_context, &self.config.context_providers);
+        server_context.insert(cfg.handler.clone());
+        // Resolve the route pattern before rendering so components can read it
+        if let Some(pattern) = cfg.resolve_route_pattern(parts.read().uri.path()) {
+            server_context.set_route_pattern(pattern);
+        }
+        // Negotiate the locale before rendering so components and the html template can read it
+        if let Some(locale) = cfg.negotiate_locale(&parts.read()) {
+            server_context.set_locale(locale);
+        }
+        // Read the theme before rendering so the page is rendered in the theme the client will hydrate with
+        if let Some(theme) = cfg.resolve_theme(&parts.read()) {
+            server_context.set_theme(theme);
+        }
+        // Generate the nonce before rendering so the renderer and components can add it to inline resources
+        let nonce = cfg.generate_nonce();
+        if let Some(nonce) = &nonce {
+            server_context.set_nonce(nonce.clone());
+        }
+        // Assign the component variants before rendering so the render and the hydration data agree
+        if let Some(assign) = &cfg.variants {
+            server_context.insert(assign(&parts.read()));
+        }
+        // Prefetch any queries for the request so components can read them while rendering
+        let query_cache = QueryCache::new();
+        if let Some(prefetch) = &cfg.prefetch_queries {
+            let prefetch = prefetch(server_context.clone(), query_cache.clone());
+            ProvideServerContext::new(prefetch, server_context.clone()).await;
+        }
+        server_context.insert(query_cache);
+
+        // Try each strategy until one succeeds
+        let mut last_error = None;
+        for &strategy in &self.render_strategies {
+            match self
+                .render_with_strategy(strategy, url.clone(), &server_context)
+                .await
+            {
+                Ok((freshness, body)) => {
+                    let body = if parts.read().method == http::Method::HEAD {
+                        // The render still needs to finish to fill the cache entry the GET request reads
+                        let cache_hit = server_context.get::<crate::render::CacheHit>().is_some();
+                        if self.head_fills_cache && !cache_hit {
+                            crate::render::spawn_platform(move || async move {
+                                _ = axum::body::to_bytes(body, usize::MAX).await;
+                            });
+                        }
+                        Body::empty()
+                    } else {
+                        body
+                    };
+                    let mut response = axum::response::Html::from(body).into_response();
+                    // The shell has rendered, so the status is final. Statuses set while the rest of the body streams are ignored
+                    *response.status_mut() = server_context.commit_response();
+                    freshness.write(response.headers_mut());
+                    response.extensions_mut().insert(RenderInfo {
+                        cache_hit: server_context.get::<crate::render::CacheHit>().is_some(),
+                        strategy,
+                    });
+                    let headers = server_context.response_parts().headers.clone();
+                    apply_request_parts_to_response(headers, &mut response);
+                    // The directive of the page wins over the default from the config
+                    let robots_tag = crate::server_context::X_ROBOTS_TAG;
+                    if let Some(robots) = &cfg.robots {
+                        if !response.headers().contains_key(&robots_tag) {
+                            match http::HeaderValue::from_str(robots) {
+                                Ok(robots) => {
+                                    response.headers_mut().insert(robots_tag, robots);
+                                }
+                                Err(err) => {
+                                    tracing::error!("Invalid X-Robots-Tag directive: {err}")
+                                }
+                            }
+                        }
+                    }
+                    // Allow the inline resources of this page through the content security policy
+                    let policy = response
+                        .headers()
+                        .get(CONTENT_SECURITY_POLICY)
+                        .and_then(|policy| policy.to_str().ok())
+                        .map(String::from)
+                        .or_else(|| cfg.content_security_policy.clone());
+                    if let Some(policy) = policy {
+                        // Pages can relax the policy for the widgets they embed
+                        let sources = server_context.csp_sources();
+                        let policy = add_sources_to_policy(
+                            &policy,
+                            sources
+                                .iter()
+                                .map(|(directive, source)| (directive.as_str(), source.as_str())),
+                        );
+                        let policy = match &nonce {
+                            Some(nonce) => add_nonce_to_policy(&policy, nonce),
+                            None => policy,
+                        };
+                        match http::HeaderValue::from_str(&policy) {
+                            Ok(policy) => {
+                                response
+                                    .headers_mut()
+                                    .insert(CONTENT_SECURITY_POLICY, policy);
+                            }
+                            Err(err) => tracing::error!("Invalid content security policy: {err}"),
+                        }
+                    }
+                    if let Some(policy) = &cfg.permissions_policy {
+                        let name = HeaderName::from_static("permissions-policy");
+                        if !response.headers().contains_key(&name) {
+                            let policy = server_context.permissions_policy(policy).to_string();
+                            match http::HeaderValue::from_str(&policy) {
+                                Ok(policy) => {
+                                    response.headers_mut().insert(name, policy);
+                                }
+                                Err(err) => tracing::error!("Invalid permissions policy: {err}"),
+                            }
+                        }
+                    }
+                    // Let the browser start fetching the hydration modules before it parses the page. Cache hits send the
+                    // links that were stored with the render
+                    let crate::render::PreloadLinks(links) =
+                        server_context.get().unwrap_or_default();
+                    for link in links {
+                        if let Ok(link) = http::HeaderValue::from_str(&link) {
+                            response.headers_mut().append(LINK, link);
+                        }
+                    }
+                    // Personalized pages must never be stored by shared caches
+                    if cfg.is_authenticated(&parts.read()) {
+                        response.headers_mut().insert(
+                            CACHE_CONTROL,
+                            http::HeaderValue::from_static("private, no-store"),
+                        );
+                    }
+                    if let Some(logs) = server_context.get::<RenderLogs>() {
+                        response.extensions_mut().insert(logs);
+                    }
+                    if let Some(on_render_outputs) = &self.on_render_outputs {
+                        on_render_outputs(&server_context.render_outputs(), &mut response);
+                    }
+                    if gone {
+                        *response.status_mut() = StatusCode::GONE;
+                    }
+                    return response;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to render page with the {strategy:?} strategy: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) => report_err(e).into_response(),
+            None => report_err("No render strategies are configured").into_response(),
+        }
+    }
+
+    /// Render the page with a fresh [`VirtualDom`] using one strategy
+    async fn render_with_strategy(
+        &self,
+        strategy: RenderStrategy,
+        url: String,
+        server_context: &DioxusServerContext,
+    ) -> Result<(dioxus_isrg::RenderFreshness, Body), dioxus_isrg::IncrementalRendererError> {
+        let build_virtual_dom = {
+            let build_virtual_dom = self.build_virtual_dom.clone();
+            let context_providers = self.config.context_providers.clone();
+            move || {
+                let mut vdom = build_virtual_dom();
+                for state in context_providers.as_slice() {
+                    vdom.insert_any_root_context(state());
+                }
+                vdom
+            }
+        };
+
+        let (freshness, rx) = self
+            .ssr_state()
+            .render(url.clone(), &self.config, build_virtual_dom, server_context)
+            .await?;
+        let mut rx = Box::pin(rx);
+
+        let body = match strategy {
+            RenderStrategy::Streaming => {
+                // Wait for the first chunk so failures before anything is sent can fall back to the next strategy
+                let first = rx.next().await.transpose()?;
+                #[cfg(not(target_arch = "wasm32"))]
+                let rx = crate::streaming::TimeoutStream::new(
+                    rx,
+                    self.config.stream_timeouts,
+                    format!(
+                        r#"<template id="dioxus-stream-timeout"></template>{}"#,
+                        self.config.index.after_closing_body_tag
+                    ),
+                    url,
+                );
+                Body::from_stream(futures_util::stream::iter(first.map(Ok)).chain(rx))
+            }
+            RenderStrategy::Buffered => {
+                let mut html = String::new();
+                while let Some(chunk) = rx.next().await {
+                    html.push_str(&chunk?);
+                }
+                // The chunks are compact, so the page is indented once it is complete
+                if self.config.pretty_html {
+                    html = crate::render::pretty_html(&html);
+                }
+                Body::from(html)
+            }
+        };
+
+        Ok((freshness, body))
+    }
 }
 
 /// SSR renderer handler for Axum with added context injection.
@@ -376,60 +1446,187 @@ pub async fn render_handler(
     State(state): State<RenderHandleState>,
     request: Request<Body>,
 ) -> impl IntoResponse {
-    // Only respond to requests for HTML
-    if let Some(mime) = request.headers().get("Accept") {
-        let mime = mime.to_str().map(|mime| mime.to_ascii_lowercase());
-        match mime {
-            Ok(accepts) if accepts.contains("text/html") => {}
-            _ => return Err(StatusCode::NOT_ACCEPTABLE),
-        }
-    }
-
-    let cfg = &state.config;
-    let ssr_state = state.ssr_state();
-    let build_virtual_dom = {
-        let build_virtual_dom = state.build_virtual_dom.clone();
-        let context_providers = state.config.context_providers.clone();
-        move || {
-            let mut vdom = build_virtual_dom();
-            for state in context_providers.as_slice() {
-                vdom.insert_any_root_context(state());
+    state.render(request).await
+}
+
+impl RenderHandleState {
+    /// Render the page for a request. This is the core of [`render_handler`], but it accepts a request with any body type, so it can
+    /// be driven from a raw hyper or `tower::Service` without axum. The body is only read by [`MethodBehavior::custom`] handlers. The returned [`Body`]
+    /// implements `http_body::Body` so it can be sent by any server built on hyper.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// async fn handle(
+    ///     state: RenderHandleState,
+    ///     request: http::Request<String>,
+    /// ) -> Result<http::Response<axum::body::Body>, std::convert::Infallible> {
+    ///     Ok(state.render(request).await)
+    /// }
+    /// ```
+    pub async fn render<B>(&self, request: http::Request<B>) -> Response<Body>
+    where
+        B: axum::body::HttpBody<Data = axum::body::Bytes> + Send + 'static,
+        B::Error: Into<axum::BoxError>,
+    {
+        let (mut parts, body) = request.into_parts();
+        host::normalize_host(&mut parts, self.config.handler.host_normalization);
+        if let Some(response) = self
+            .short_circuit
+            .as_ref()
+            .and_then(|short_circuit| short_circuit(&parts))
+        {
+            return response;
+        }
+        if let Some(response) = self.cold_start_response() {
+            return response;
+        }
+
+        let behavior = self
+            .methods
+            .iter()
+            .find(|(method, _)| *method == parts.method)
+            .map(|(_, behavior)| behavior);
+        let mut form_cookies = Vec::new();
+        match behavior {
+            Some(MethodBehavior::Render) => {}
+            Some(MethodBehavior::Custom(MethodHandler(handler))) => {
+                let request = Request::from_parts(parts.clone(), Body::new(body));
+                if let Some(response) = handler(request).await {
+                    return response;
+                }
             }
-            vdom
+            Some(MethodBehavior::ServerFnForm) => {
+                match self.run_form_server_fn(&mut parts, Body::new(body)).await {
+                    Ok(cookies) => form_cookies = cookies,
+                    Err(response) => return response,
+                }
+            }
+            Some(MethodBehavior::NotAllowed) | None => return self.method_not_allowed(),
         }
-    };
 
-    let (parts, _) = request.into_parts();
-    let url = parts
-        .uri
-        .path_and_query()
-        .ok_or(StatusCode::BAD_REQUEST)?
-        .to_string();
-    let parts: Arc<parking_lot::RwLock<http::request::Parts>> =
-        Arc::new(parking_lot::RwLock::new(parts));
-    // Create the server context with info from the request
-    let server_context = DioxusServerContext::from_shared_parts(parts.clone());
-    // Provide additional context from the render state
-    add_server_context(&server_context, &state.config.context_providers);
-
-    match ssr_state
-        .render(url, cfg, build_virtual_dom, &server_context)
-        .await
-    {
-        Ok((freshness, rx)) => {
-            let mut response = axum::response::Html::from(Body::from_stream(rx)).into_response();
-            freshness.write(response.headers_mut());
-            let headers = server_context.response_parts().headers.clone();
-            apply_request_parts_to_response(headers, &mut response);
-            Ok(response)
+        // Time the render with the configured clock so tests can control the reported duration
+        let time_source = self.config.time_source();
+        let start = time_source.now();
+        let elapsed = || time_source.now().duration_since(start).unwrap_or_default();
+        let parts: Arc<parking_lot::RwLock<http::request::Parts>> =
+            Arc::new(parking_lot::RwLock::new(parts));
+
+        let sampled = self.is_trace_sampled(&parts.read());
+        let mut response = if sampled {
+            let span = render_span(&self.config, &parts.read());
+            let response = self.respond(parts.clone()).instrument(span.clone()).await;
+            span.record("status", response.status().as_u16());
+            response
+        } else {
+            let response = self.respond(parts.clone()).await;
+            // Errors are always traced, even if the render was not sampled
+            if self.trace_renders.is_some() && response.status().is_server_error() {
+                let span = render_span(&self.config, &parts.read());
+                span.record("status", response.status().as_u16());
+                span.in_scope(|| tracing::error!("Render failed after {:?}", elapsed()));
+            }
+            response
+        };
+        for cookie in form_cookies {
+            response.headers_mut().append(SET_COOKIE, cookie);
         }
-        Err(e) => {
-            tracing::error!("Failed to render page: {}", e);
-            Ok(report_err(e).into_response())
+        // Check the budget after every header is added
+        limit_response_headers(
+            response.headers_mut(),
+            self.config.handler.max_response_header_size,
+        );
+
+        if let Some(on_response) = &self.on_response {
+            on_response(&parts.read(), &response, elapsed());
         }
+
+        response
     }
 }
 
+/// The largest page of routes [`incremental_debug_handler`] returns
+const MAX_DEBUG_PAGE_SIZE: usize = 1000;
+
+/// The query parameters of [`incremental_debug_handler`]
+#[derive(serde::Deserialize)]
+struct IncrementalDebugQuery {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+    #[serde(default)]
+    prefix: String,
+}
+
+/// A handler that lists the routes in the memory cache of incremental rendering as JSON. Don't expose this handler publicly.
+///
+/// The routes are sorted and paginated with the `offset` and `limit` query parameters. `limit` defaults to 100 routes and is capped
+/// at 1000. Only routes that start with the `prefix` query parameter are included. The response contains the page of routes and the
+/// total number of matching routes:
+///
+/// ```json
+/// { "total": 2, "offset": 0, "limit": 100, "entries": [{ "route": "/blog/1", "created_at": "2024-01-01T00:00:00+00:00", "size": 1024 }] }
+/// ```
+///
+/// # Example
+/// ```rust,no_run
+/// # use dioxus::prelude::*;
+/// # fn app() -> Element { todo!() }
+/// use axum::routing::{any, get};
+///
+/// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app);
+/// let router: axum::Router = axum::Router::new()
+///     .route("/_debug/cache", get(incremental_debug_handler))
+///     .fallback(any(render_handler))
+///     .with_state(state);
+/// ```
+pub async fn incremental_debug_handler(
+    State(state): State<RenderHandleState>,
+    request: Request<Body>,
+) -> Response<Body> {
+    let query = match axum::extract::Query::<IncrementalDebugQuery>::try_from_uri(request.uri()) {
+        Ok(axum::extract::Query(query)) => query,
+        Err(err) => return err.into_response(),
+    };
+    let limit = query.limit.unwrap_or(100).min(MAX_DEBUG_PAGE_SIZE);
+
+    let Some((total, entries)) =
+        state
+            .ssr_state()
+            .incremental_cache_page(&query.prefix, query.offset, limit)
+    else {
+        return (
+            StatusCode::NOT_FOUND,
+            "Incremental rendering is not enabled",
+        )
+            .into_response();
+    };
+
+    axum::Json(serde_json::json!({
+        "total": total,
+        "offset": query.offset,
+        "limit": limit,
+        "entries": entries,
+    }))
+    .into_response()
+}
+
+fn render_span(cfg: &ServeConfig, parts: &http::request::Parts) -> tracing::Span {
+    let pattern = cfg.resolve_route_pattern(parts.uri.path());
+    let span = tracing::info_span!(
+        "render",
+        otel.name = %cfg.span_name(parts, pattern.as_deref()),
+        route = pattern.as_deref(),
+        method = %parts.method,
+        uri = %parts.uri,
+        status = tracing::field::Empty,
+        trace_id = tracing::field::Empty,
+        parent_span_id = tracing::field::Empty,
+    );
+    crate::trace_context::record_trace_context(&span, &parts.headers);
+    span
+}
+
 fn report_err<E: std::fmt::Display>(e: E) -> Response<axum::body::Body> {
     Response::builder()
         .status(StatusCode::INTERNAL_SERVER_ERROR)
@@ -441,6 +1638,7 @@ fn report_err<E: std::fmt::Display>(e: E) -> Response<axum::body::Body> {
 async fn handle_server_fns_inner(
     path: &str,
     additional_context: ContextProviders,
+    handler: Arc<crate::serve_config::HandlerConfig>,
     req: Request<Body>,
 ) -> impl IntoResponse {
     use server_fn::middleware::Service;
@@ -448,16 +1646,72 @@ async fn handle_server_fns_inner(
     let path_string = path.to_string();
 
     let future = move || async move {
-        let (parts, body) = req.into_parts();
-        let req = Request::from_parts(parts.clone(), body);
+        // The server context takes ownership of the request parts and the server function gets a copy. Both the middleware of the
+        // server function and the server context read the headers and extensions, so this still clones every header and deep clones
+        // every extension of the request
+        let (mut parts, body) = req.into_parts();
+        host::normalize_host(&mut parts, handler.host_normalization);
+        let body = match handler.max_server_fn_body_size {
+            Some(limit) => Body::new(http_body_util::Limited::new(body, limit)),
+            None => body,
+        };
+        let mut req = Request::new(body);
+        *req.method_mut() = parts.method.clone();
+        *req.uri_mut() = parts.uri.clone();
+        *req.version_mut() = parts.version;
+        *req.headers_mut() = parts.headers.clone();
+        *req.extensions_mut() = parts.extensions.clone();
 
         if let Some(mut service) =
             server_fn::axum::get_server_fn_service(&path_string)
         {
+            // Reject uploads before reading the body. Hyper only sends `100 Continue` to clients that expect it once the body is read
+            if let Some(rejection) = reject_upload(&parts, handler.max_server_fn_body_size) {
+                return rejection;
+            }
+
+            // Serve responses from server functions that opted into caching without running them again. The server function and its
+            // middleware don't run for cache hits, so private responses are only shared between callers without credentials
+            let cache_key =
+                (parts.method == http::Method::GET).then(|| server_fn_cache::cache_key(&parts.uri));
+            let credentials = server_fn_cache::has_credentials(&parts.headers);
+            if let Some(cache_key) = &cache_key {
+                if let Some(cached) =
+                    handler
+                        .server_fn_cache
+                        .get(cache_key, &parts.headers, handler.time_source.now())
+                {
+                    return cached;
+                }
+            }
+
             // Create the server context with info from the request
             let server_context = DioxusServerContext::new(parts);
             // Provide additional context from the render state
             add_server_context(&server_context, &additional_context);
+            server_context.insert(handler.clone());
+
+            // Buffer form bodies so the server function can read them with `DioxusServerContext::form` after its arguments are decoded.
+            // Only server functions that read the form are buffered, and never past the form limit
+            if handler.form_server_fns.contains(path_string.as_str())
+                && is_form_urlencoded(req.headers())
+            {
+                let body = std::mem::take(req.body_mut());
+                let form = match axum::body::to_bytes(body, handler.max_form_body_size).await {
+                    Ok(form) => form,
+                    Err(err) => return body_error_status(&err).into_response(),
+                };
+                server_context.insert(crate::server_context::FormBody(form.clone()));
+                *req.body_mut() = Body::from(form);
+            }
+
+            // Normalize the arguments before the server function decodes them
+            req = match server_fn_args::transform_request(&path_string, &handler, req, &server_context)
+                .await
+            {
+                Ok(req) => req,
+                Err(rejection) => return rejection,
+            };
 
             // store Accepts and Referrer in case we need them for redirect (below)
             let accepts_html = req
@@ -468,9 +1722,34 @@ async fn handle_server_fns_inner(
                 .unwrap_or(false);
             let referrer = req.headers().get(REFERER).cloned();
 
+            // Cancel the token of the request if the handler is dropped before the server function returns, for example because the client disconnected
+            let cancel_on_drop = server_context.cancellation_token().drop_guard();
+
+            // Run the server function in a span that continues the trace of the caller
+            let span = tracing::info_span!(
+                "server_fn",
+                path = %path_string,
+                trace_id = tracing::field::Empty,
+                parent_span_id = tracing::field::Empty,
+            );
+            crate::trace_context::record_trace_context(&span, req.headers());
+
             // actually run the server fn (which may use the server context)
             let fut = with_server_context(server_context.clone(), || service.run(req));
-            let mut res = ProvideServerContext::new(fut, server_context.clone()).await;
+            let mut res = ProvideServerContext::new(fut, server_context.clone())
+                .instrument(span)
+                .await;
+            cancel_on_drop.disarm();
+
+            // Apply the status the server function set, for example with a rejection from `DioxusServerContext::form`
+            let status = server_context.response_parts().status;
+            if status != StatusCode::OK {
+                *res.status_mut() = status;
+            }
+
+            if res.status().is_server_error() {
+                res = server_fn_errors::report_error_response(&path_string, &handler, res).await;
+            }
 
             // it it accepts text/html (i.e., is a plain form post) and doesn't already have a
             // Location set, then redirect to Referer
@@ -484,9 +1763,37 @@ async fn handle_server_fns_inner(
                 }
             }
 
+            // Send the response registered for domain errors instead of the serialized error
+            if res.status() == StatusCode::INTERNAL_SERVER_ERROR {
+                res = server_fn_errors::map_error_response(&handler, res).await;
+            }
+
             // apply the response parts from the server context to the response
-            let mut res_options = server_context.response_parts_mut();
-            res.headers_mut().extend(res_options.headers.drain());
+            let cache_policy = {
+                let mut res_options = server_context.response_parts_mut();
+                res.headers_mut().extend(res_options.headers.drain());
+                limit_set_cookie_headers(res.headers_mut(), handler.max_set_cookie_headers);
+                limit_response_headers(res.headers_mut(), handler.max_response_header_size);
+                res_options
+                    .extensions
+                    .remove::<crate::server_context::ServerFnCachePolicy>()
+            };
+
+            // Store the response if the server function opted into caching
+            if let (Some(cache_key), Some(cache_policy)) = (cache_key, cache_policy) {
+                if res.status().is_success() {
+                    res = handler
+                        .server_fn_cache
+                        .store(
+                            cache_key,
+                            cache_policy,
+                            credentials,
+                            res,
+                            handler.time_source.now(),
+                        )
+                        .await;
+                }
+            }
 
             Ok(res)
         } else {
@@ -0,0 +1,117 @@
+//! Transform the arguments of server functions before they run. See
+//! [`ServeConfigBuilder::transform_server_fn_args`](crate::prelude::ServeConfigBuilder::transform_server_fn_args)
+
+use axum::body::Body;
+use axum::response::IntoResponse;
+use bytes::Bytes;
+use http::{header, Request, Response};
+use std::sync::Arc;
+
+use crate::prelude::*;
+use crate::serve_config::HandlerConfig;
+
+/// How the arguments of a request are encoded
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ArgsEncoding {
+    Url,
+    Json,
+}
+
+/// The maximum size of a body that is buffered to transform its arguments if
+/// [`ServeConfigBuilder::max_server_fn_body_size`](crate::prelude::ServeConfigBuilder::max_server_fn_body_size) is not set
+pub(crate) const DEFAULT_MAX_TRANSFORM_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// Decode the arguments, transform them and encode them again in the same encoding
+pub(crate) type ArgsTransform =
+    Arc<dyn Fn(ArgsEncoding, &[u8]) -> Result<Vec<u8>, String> + Send + Sync>;
+
+/// Create a transform that decodes the arguments of the server function `F` in either encoding
+pub(crate) fn args_transform<F>(transform: impl Fn(F) -> F + Send + Sync + 'static) -> ArgsTransform
+where
+    F: server_fn::ServerFn + serde::Serialize + serde::de::DeserializeOwned,
+{
+    Arc::new(move |encoding, args| match encoding {
+        ArgsEncoding::Url => {
+            let args = serde_urlencoded::from_bytes::<F>(args).map_err(|err| err.to_string())?;
+            serde_urlencoded::to_string(transform(args))
+                .map(String::into_bytes)
+                .map_err(|err| err.to_string())
+        }
+        ArgsEncoding::Json => {
+            let args = serde_json::from_slice::<F>(args).map_err(|err| err.to_string())?;
+            serde_json::to_vec(&transform(args)).map_err(|err| err.to_string())
+        }
+    })
+}
+
+/// Transform the arguments of a request to the server function at `path` with the transform registered for it
+pub(crate) async fn transform_request(
+    path: &str,
+    handler: &HandlerConfig,
+    mut req: Request<Body>,
+    server_context: &DioxusServerContext,
+) -> Result<Request<Body>, Response<Body>> {
+    let Some(args_transform) = handler.server_fn_args.get(path) else {
+        return Ok(req);
+    };
+    let transform = |encoding, args: &[u8]| {
+        let transformed =
+            with_server_context(server_context.clone(), || args_transform(encoding, args));
+        if let Err(err) = &transformed {
+            tracing::debug!("Failed to transform the arguments of server function {path}: {err}");
+        }
+        transformed.ok()
+    };
+
+    // `GetUrl` arguments are in the query
+    if req.method() == http::Method::GET {
+        let query = req.uri().query().unwrap_or_default();
+        if let Some(query) = transform(ArgsEncoding::Url, query.as_bytes()) {
+            let path_and_query =
+                format!("{}?{}", req.uri().path(), String::from_utf8_lossy(&query));
+            let mut uri = req.uri().clone().into_parts();
+            uri.path_and_query = path_and_query.parse().ok();
+            if let Ok(uri) = http::Uri::from_parts(uri) {
+                *req.uri_mut() = uri;
+            }
+        }
+        return Ok(req);
+    }
+
+    let mime = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+    let encoding = match mime.as_deref() {
+        Some("application/x-www-form-urlencoded") => ArgsEncoding::Url,
+        Some("application/json") => ArgsEncoding::Json,
+        _ => return Ok(req),
+    };
+
+    let body = std::mem::take(req.body_mut());
+    let limit = handler
+        .max_server_fn_body_size
+        .unwrap_or(DEFAULT_MAX_TRANSFORM_BODY_SIZE);
+    let body = match axum::body::to_bytes(body, limit).await {
+        Ok(body) => body,
+        Err(err) => return Err(super::body_error_status(&err).into_response()),
+    };
+    let body = match transform(encoding, &body) {
+        Some(transformed) => {
+            let transformed = Bytes::from(transformed);
+            // Form fields read from the server context should match the arguments the server function gets
+            if matches!(encoding, ArgsEncoding::Url) && handler.form_server_fns.contains(path) {
+                server_context.insert(crate::server_context::FormBody(transformed.clone()));
+            }
+            req.headers_mut()
+                .insert(header::CONTENT_LENGTH, transformed.len().into());
+            transformed
+        }
+        None => body,
+    };
+    *req.body_mut() = Body::from(body);
+
+    Ok(req)
+}
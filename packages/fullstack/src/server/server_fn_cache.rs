@@ -0,0 +1,340 @@
+//! An in memory cache for the responses of `GET` server functions that opted into HTTP caching with
+//! [`DioxusServerContext::cache_response_for`](crate::prelude::DioxusServerContext::cache_response_for)
+
+use axum::body::Body;
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue, Response, StatusCode, Uri};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::serve_config::HandlerConfig;
+use crate::server_context::ServerFnCachePolicy;
+
+/// The default number of responses the server function cache holds. See
+/// [`ServeConfigBuilder::server_fn_cache_capacity`](crate::prelude::ServeConfigBuilder::server_fn_cache_capacity)
+pub const DEFAULT_SERVER_FN_CACHE_CAPACITY: usize = 1000;
+
+/// The request headers that identify the caller. Private responses vary on them
+const CREDENTIAL_HEADERS: &str = "Cookie, Authorization";
+
+/// How often the cache removes every entry that is out of date
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The cached responses of the server functions of one router. The least recently used response is removed once the cache is full
+pub(crate) struct ServerFnCache {
+    entries: Option<Mutex<Entries>>,
+}
+
+struct Entries {
+    responses: LruCache<String, CachedResponse>,
+    last_sweep: SystemTime,
+}
+
+impl Default for ServerFnCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SERVER_FN_CACHE_CAPACITY)
+    }
+}
+
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    etag: HeaderValue,
+    created_at: SystemTime,
+    ttl: Duration,
+    public: bool,
+}
+
+impl CachedResponse {
+    fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.created_at).unwrap_or_default()
+    }
+
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        self.age(now) < self.ttl
+    }
+
+    fn to_response(&self, if_none_match: Option<&HeaderValue>, now: SystemTime) -> Response<Body> {
+        let age = self.age(now);
+        let max_age = self.ttl.saturating_sub(age);
+
+        // If the client already has this version of the response, we don't need to send the body again
+        let mut response = if if_none_match == Some(&self.etag) {
+            let mut response = Response::new(Body::empty());
+            *response.status_mut() = StatusCode::NOT_MODIFIED;
+            response
+        } else {
+            let mut response = Response::new(Body::from(self.body.clone()));
+            *response.status_mut() = self.status;
+            *response.headers_mut() = self.headers.clone();
+            response
+        };
+
+        let headers = response.headers_mut();
+        headers.insert(header::AGE, age.as_secs().into());
+        write_cache_headers(headers, max_age, self.etag.clone(), self.public);
+        response
+    }
+}
+
+/// Check if a request carries credentials. The response to these requests may depend on the caller, so they are only served from
+/// and stored in the cache if the server function marked its response as public
+pub(crate) fn has_credentials(headers: &HeaderMap) -> bool {
+    headers.contains_key(header::COOKIE) || headers.contains_key(header::AUTHORIZATION)
+}
+
+/// Get the key a request is cached under. The key is built from the path and the decoded arguments in the query, so requests with
+/// the same arguments share an entry even if the parameters are in a different order or encoded differently.
+pub(crate) fn cache_key(uri: &Uri) -> String {
+    args_key(uri.path(), uri.query().unwrap_or_default())
+}
+
+/// Get the key a server function at `path` with the url encoded arguments `query` is cached under
+fn args_key(path: &str, query: &str) -> String {
+    let Ok(mut args) = serde_urlencoded::from_str::<Vec<(String, String)>>(query) else {
+        return format!("{path}?{query}");
+    };
+    // The sort is stable, so repeated parameters keep the order of the sequence they encode
+    args.sort_by(|(first, _), (second, _)| first.cmp(second));
+    match serde_urlencoded::to_string(&args) {
+        Ok(args) if !args.is_empty() => format!("{path}?{args}"),
+        _ => path.to_string(),
+    }
+}
+
+/// Remove the cached response of a `GET` server function for one set of arguments. Other arguments stay cached. Call this after
+/// changing the data the server function reads so the next request runs it again.
+///
+/// The response is removed from the cache of the handler of the current request, so this must be called from a server function or
+/// while rendering a page.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// #[server(input = GetUrl)]
+/// async fn scores(game: String) -> Result<Vec<u32>, ServerFnError> {
+///     server_context().cache_response_for(std::time::Duration::from_secs(60));
+///     Ok(vec![42])
+/// }
+///
+/// #[server]
+/// async fn submit_score(game: String, score: u32) -> Result<(), ServerFnError> {
+///     // Store the score here
+///     invalidate_server_fn_response(&Scores { game });
+///     Ok(())
+/// }
+/// ```
+pub fn invalidate_server_fn_response<F: server_fn::ServerFn + serde::Serialize>(args: &F) {
+    let query = match serde_urlencoded::to_string(args) {
+        Ok(query) => query,
+        Err(err) => {
+            tracing::error!(
+                "Failed to encode the arguments of server function {}: {err}",
+                F::PATH
+            );
+            return;
+        }
+    };
+    let key = args_key(F::PATH, &query);
+    if let Some(handler) = crate::prelude::server_context().get::<Arc<HandlerConfig>>() {
+        handler.server_fn_cache.remove(&key);
+    }
+}
+
+impl ServerFnCache {
+    /// Create a cache that holds up to `capacity` responses. A capacity of zero disables the cache
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity).map(|capacity| {
+                Mutex::new(Entries {
+                    responses: LruCache::new(capacity),
+                    last_sweep: SystemTime::UNIX_EPOCH,
+                })
+            }),
+        }
+    }
+
+    /// Try to get a fresh response from the cache for a request with `headers`
+    pub(crate) fn get(
+        &self,
+        key: &str,
+        headers: &HeaderMap,
+        now: SystemTime,
+    ) -> Option<Response<Body>> {
+        let mut entries = self.entries.as_ref()?.lock();
+        let cached = entries.responses.get(key)?;
+        if cached.is_fresh(now) {
+            // Private responses were rendered for an anonymous caller, so they can't be sent to a caller with credentials
+            if !cached.public && has_credentials(headers) {
+                return None;
+            }
+            tracing::trace!("server function cache hit {key}");
+            return Some(cached.to_response(headers.get(header::IF_NONE_MATCH), now));
+        }
+
+        // The entry is out of date
+        entries.responses.pop(key);
+        None
+    }
+
+    /// Remove the response stored under `key`
+    fn remove(&self, key: &str) {
+        if let Some(entries) = &self.entries {
+            entries.lock().responses.pop(key);
+        }
+    }
+
+    /// Store a response in the cache and return the response with the cache headers added. Private responses to requests with
+    /// credentials are not stored
+    pub(crate) async fn store(
+        &self,
+        key: String,
+        policy: ServerFnCachePolicy,
+        credentials: bool,
+        response: Response<Body>,
+        now: SystemTime,
+    ) -> Response<Body> {
+        let (mut parts, body) = response.into_parts();
+        let body = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to read the response body of server function {key} for caching: {err}"
+                );
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::from(format!("Error: {err}")))
+                    .unwrap();
+            }
+        };
+
+        let etag = etag(&body);
+        write_cache_headers(&mut parts.headers, policy.ttl, etag.clone(), policy.public);
+
+        // The cache headers are still sent if the response is not stored so clients can cache the response
+        let entries = self
+            .entries
+            .as_ref()
+            .filter(|_| policy.public || !credentials);
+        if let Some(entries) = entries {
+            // Never share cookies between responses
+            let mut headers = parts.headers.clone();
+            headers.remove(header::SET_COOKIE);
+
+            let mut entries = entries.lock();
+            entries.sweep(now);
+            entries.responses.put(
+                key,
+                CachedResponse {
+                    status: parts.status,
+                    headers,
+                    body: body.clone(),
+                    etag,
+                    created_at: now,
+                    ttl: policy.ttl,
+                    public: policy.public,
+                },
+            );
+        }
+
+        Response::from_parts(parts, Body::from(body))
+    }
+}
+
+impl Entries {
+    /// Remove every response that is out of date if the last sweep was long enough ago. Responses that are never requested again
+    /// would otherwise only be removed once the cache is full
+    fn sweep(&mut self, now: SystemTime) {
+        if now.duration_since(self.last_sweep).unwrap_or_default() < SWEEP_INTERVAL {
+            return;
+        }
+        self.last_sweep = now;
+
+        let expired: Vec<String> = self
+            .responses
+            .iter()
+            .filter(|(_, cached)| !cached.is_fresh(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            self.responses.pop(&key);
+        }
+    }
+}
+
+fn write_cache_headers(
+    headers: &mut HeaderMap,
+    max_age: Duration,
+    etag: HeaderValue,
+    public: bool,
+) {
+    let visibility = if public { "public" } else { "private" };
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_str(&format!("{visibility}, max-age={}", max_age.as_secs())).unwrap(),
+    );
+    headers.insert(header::ETAG, etag);
+    // Shared caches in front of the server must not send a private response to another caller
+    let varies_on_credentials = headers
+        .get_all(header::VARY)
+        .iter()
+        .any(|vary| vary == CREDENTIAL_HEADERS);
+    if !public && !varies_on_credentials {
+        headers.append(header::VARY, HeaderValue::from_static(CREDENTIAL_HEADERS));
+    }
+}
+
+fn etag(body: &[u8]) -> HeaderValue {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    body.hash(&mut hasher);
+    HeaderValue::from_str(&format!("\"{:x}\"", hasher.finish())).unwrap()
+}
+
+#[test]
+fn evicts_and_expires_responses() {
+    use futures_util::FutureExt;
+
+    let cache = ServerFnCache::new(1);
+    let start = SystemTime::UNIX_EPOCH;
+    let policy = ServerFnCachePolicy {
+        ttl: Duration::from_secs(10),
+        public: false,
+    };
+    let anonymous = HeaderMap::new();
+    let store = |key: &str| {
+        cache
+            .store(
+                key.to_string(),
+                policy,
+                false,
+                Response::new(Body::from("cached")),
+                start,
+            )
+            .now_or_never()
+            .unwrap()
+    };
+
+    store("/api/a");
+    assert!(cache
+        .get("/api/a", &anonymous, start + Duration::from_secs(5))
+        .is_some());
+    assert!(cache
+        .get("/api/a", &anonymous, start + Duration::from_secs(10))
+        .is_none());
+
+    // The cache only holds one response, so storing another removes the first
+    store("/api/a");
+    store("/api/b");
+    assert!(cache.get("/api/a", &anonymous, start).is_none());
+    assert!(cache.get("/api/b", &anonymous, start).is_some());
+
+    // Private responses are never sent to callers with credentials
+    let mut signed_in = HeaderMap::new();
+    signed_in.insert(header::COOKIE, HeaderValue::from_static("session=1"));
+    assert!(cache.get("/api/b", &signed_in, start).is_none());
+}
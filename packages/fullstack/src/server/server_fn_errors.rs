@@ -0,0 +1,91 @@
+//! Custom responses for domain errors returned from server functions. See
+//! [`ServeConfigBuilder::register_server_fn_error`](crate::prelude::ServeConfigBuilder::register_server_fn_error) and
+//! [`ServeConfigBuilder::on_server_fn_error`](crate::prelude::ServeConfigBuilder::on_server_fn_error)
+
+use axum::body::Body;
+use http::{header, Response, StatusCode};
+use server_fn::error::{NoCustomError, ServerFnErrorSerde};
+use server_fn::ServerFnError;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::serve_config::HandlerConfig;
+
+pub(crate) type ErrorMapping = Arc<dyn Fn(&str) -> Option<(StatusCode, Body)> + Send + Sync>;
+
+pub(crate) type ErrorLogger = Arc<dyn Fn(&str, &ServerFnError) + Send + Sync>;
+
+/// Run the callback from [`ServeConfigBuilder::on_server_fn_error`](crate::prelude::ServeConfigBuilder::on_server_fn_error) for a
+/// failed server function. The body of the response is buffered to read the error.
+pub(crate) async fn report_error_response(
+    path: &str,
+    handler: &HandlerConfig,
+    response: Response<Body>,
+) -> Response<Body> {
+    let Some(logger) = &handler.error_logger else {
+        return response;
+    };
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed to read the error response of a server function: {err}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let serialized = String::from_utf8_lossy(&body);
+    // Custom errors can't be parsed without their type, so they are reported with their display output
+    let err = match serialized.strip_prefix("WrappedServerError|") {
+        Some(custom) => ServerFnError::ServerError(custom.to_string()),
+        None => ServerFnError::<NoCustomError>::de(&serialized),
+    };
+    logger(path, &err);
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Create an error mapping that parses custom errors of type `E`
+pub(crate) fn error_mapping<E>(
+    mapping: impl Fn(&E) -> (StatusCode, Body) + Send + Sync + 'static,
+) -> ErrorMapping
+where
+    E: FromStr + Display + 'static,
+{
+    Arc::new(move |serialized| match ServerFnError::<E>::de(serialized) {
+        ServerFnError::WrappedServerError(err) => Some(mapping(&err)),
+        _ => None,
+    })
+}
+
+/// Replace the response of a failed server function with the response registered for its error if there is one
+pub(crate) async fn map_error_response(
+    handler: &HandlerConfig,
+    response: Response<Body>,
+) -> Response<Body> {
+    let mappings = &handler.error_mappings;
+    if mappings.is_empty() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed to read the error response of a server function: {err}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let serialized = String::from_utf8_lossy(&body);
+    if let Some((status, mapped)) = mappings.iter().find_map(|mapping| mapping(&serialized)) {
+        parts.status = status;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.remove(header::CONTENT_TYPE);
+        return Response::from_parts(parts, mapped);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
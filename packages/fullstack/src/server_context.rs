@@ -194,6 +194,38 @@ mod server_fn_impl {
             self.response_parts.write()
         }
 
+        /// Change several headers of the response at once. The closure edits a copy of the headers, which replaces the headers of the
+        /// response only if the closure returns `Ok`. If it returns an error or panics, none of its changes are applied.
+        ///
+        /// The response parts stay locked while the closure runs so other writers can't add headers that would be lost when the
+        /// copy replaces the headers. The closure must not read or write the response parts of the server context, for example with
+        /// [`Self::response_parts`], or it will deadlock.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn download() -> Result<String, ServerFnError> {
+        ///     server_context().with_response_headers(|headers| {
+        ///         headers.insert("Content-Disposition", "attachment; filename=\"report.csv\"".parse()?);
+        ///         headers.insert("X-Report-Version", "2".parse()?);
+        ///         Ok::<_, ServerFnError>(())
+        ///     })?;
+        ///     Ok("id,total".to_string())
+        /// }
+        /// ```
+        pub fn with_response_headers<T, E>(
+            &self,
+            f: impl FnOnce(&mut http::HeaderMap) -> Result<T, E>,
+        ) -> Result<T, E> {
+            let mut response_parts = self.response_parts_mut();
+            let mut headers = response_parts.headers.clone();
+            let result = f(&mut headers)?;
+            response_parts.headers = headers;
+            Ok(result)
+        }
+
         /// Get the request parts
         ///
         #[doc = include_str!("../docs/request_origin.md")]
@@ -259,9 +291,663 @@ mod server_fn_impl {
         pub async fn extract<M, T: FromServerContext<M>>(&self) -> Result<T, T::Rejection> {
             T::from_request(self).await
         }
+
+        /// Get the route pattern (for example `/products/:id`) that matched the current request. Unlike the
+        /// concrete path, the pattern is shared by every request to the same route which makes it useful for
+        /// aggregating metrics.
+        ///
+        /// The pattern is set while rendering a page if a resolver was provided with
+        /// [`ServeConfigBuilder::route_pattern`](crate::prelude::ServeConfigBuilder::route_pattern)
+        /// or if it was set manually with [`Self::set_route_pattern`].
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[component]
+        /// fn Product(id: usize) -> Element {
+        ///     server_only! {
+        ///         if let Some(pattern) = server_context().route_pattern() {
+        ///             println!("rendering {pattern}");
+        ///         }
+        ///     }
+        ///     rsx! { "Product {id}" }
+        /// }
+        /// ```
+        pub fn route_pattern(&self) -> Option<String> {
+            self.request_parts()
+                .extensions
+                .get::<RoutePattern>()
+                .map(|pattern| pattern.0.clone())
+        }
+
+        /// Mark the response of the current server function as cacheable for `ttl`. This only has an effect for server functions that
+        /// use the `GET` method.
+        ///
+        /// The response will be sent with `Cache-Control: private` and `ETag` headers and stored in memory keyed by the path and the
+        /// arguments in the query of the request. Requests with the same arguments within the `ttl` are served from the cache without
+        /// running the server function again, even if the parameters are in a different order. Each set of arguments is cached and
+        /// expires on its own. Remove one set of arguments from the cache early with
+        /// [`invalidate_server_fn_response`](crate::prelude::invalidate_server_fn_response). `Set-Cookie` headers are never stored in
+        /// the cache.
+        ///
+        /// Cached responses are sent before the server function and its middleware run, so the cache is only shared between requests
+        /// without a `Cookie` or `Authorization` header. Requests with credentials always run the server function. If the response is
+        /// the same for every caller, use [`Self::cache_response_publicly_for`] instead.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server(input = GetUrl)]
+        /// async fn leaderboard() -> Result<Vec<String>, ServerFnError> {
+        ///     server_context().cache_response_for(std::time::Duration::from_secs(60));
+        ///     Ok(vec!["Ferris".to_string()])
+        /// }
+        /// ```
+        pub fn cache_response_for(&self, ttl: std::time::Duration) {
+            self.response_parts_mut()
+                .extensions
+                .insert(ServerFnCachePolicy { ttl, public: false });
+        }
+
+        /// Mark the response of the current server function as cacheable for `ttl` for every caller. This works like
+        /// [`Self::cache_response_for`], but the response is sent with `Cache-Control: public` and the cached response is also sent
+        /// to requests with credentials without running the server function or its middleware. Only use this if the response does not
+        /// depend on who sent the request.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server(input = GetUrl)]
+        /// async fn release_notes() -> Result<String, ServerFnError> {
+        ///     server_context().cache_response_publicly_for(std::time::Duration::from_secs(3600));
+        ///     Ok("Version 1.0".to_string())
+        /// }
+        /// ```
+        pub fn cache_response_publicly_for(&self, ttl: std::time::Duration) {
+            self.response_parts_mut()
+                .extensions
+                .insert(ServerFnCachePolicy { ttl, public: true });
+        }
+
+        /// Write a value from inside the render that the server can read after rendering, for example the id of the entity a page
+        /// rendered to log it or set a header. Outputs are read with [`RenderHandleState::with_render_outputs`](crate::prelude::RenderHandleState::with_render_outputs).
+        /// Setting an output of the same type again replaces the old value.
+        ///
+        /// When the page is streamed, only outputs that are set before the first chunk of html is sent are observable. That
+        /// includes everything set during the initial render, but not outputs set after a suspense boundary resolves. When the
+        /// page is buffered, outputs from the whole render are observable. Pages served from the incremental cache are not
+        /// rendered, so they have no outputs.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[derive(Clone)]
+        /// struct RenderedProduct(usize);
+        ///
+        /// #[component]
+        /// fn Product(id: usize) -> Element {
+        ///     server_only! {
+        ///         server_context().set_render_output(RenderedProduct(id));
+        ///     }
+        ///     rsx! { "Product {id}" }
+        /// }
+        /// ```
+        pub fn set_render_output<T: Clone + Send + Sync + 'static>(&self, value: T) {
+            let mut response_parts = self.response_parts_mut();
+            let outputs = response_parts.extensions.get_mut::<RenderOutputs>();
+            match outputs {
+                Some(outputs) => {
+                    outputs.values.insert(value);
+                }
+                None => {
+                    let mut outputs = RenderOutputs::default();
+                    outputs.values.insert(value);
+                    response_parts.extensions.insert(outputs);
+                }
+            }
+        }
+
+        /// Set the status of the page response, for example to send a `404` from the component of a route that does not exist.
+        ///
+        /// When the page is streamed, the status is sent before the first chunk of html. Statuses set during the initial render,
+        /// including by top level route components, are always sent. Statuses set after the first chunk is sent, for example after a
+        /// suspense boundary resolves, are ignored with a warning. When the page is buffered, statuses from the whole render are used.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[component]
+        /// fn NotFound() -> Element {
+        ///     server_only! {
+        ///         server_context().set_status(http::StatusCode::NOT_FOUND);
+        ///     }
+        ///     rsx! { "Page not found" }
+        /// }
+        /// ```
+        pub fn set_status(&self, status: http::StatusCode) {
+            let mut response_parts = self.response_parts_mut();
+            if response_parts
+                .extensions
+                .get::<ResponseCommitted>()
+                .is_some()
+            {
+                tracing::warn!(
+                    "Ignoring status {status} because the response was already sent to the client"
+                );
+                return;
+            }
+            response_parts.status = status;
+        }
+
+        /// Redirect the page response to `location` with a `302 Found` status. Redirects follow the same rules as [`Self::set_status`]
+        /// and are ignored with a warning after the response is sent to the client.
+        pub fn redirect(&self, location: &str) {
+            let location = match http::HeaderValue::from_str(location) {
+                Ok(location) => location,
+                Err(err) => {
+                    tracing::error!("Invalid redirect location {location:?}: {err}");
+                    return;
+                }
+            };
+            let mut response_parts = self.response_parts_mut();
+            if response_parts
+                .extensions
+                .get::<ResponseCommitted>()
+                .is_some()
+            {
+                tracing::warn!(
+                    "Ignoring redirect to {location:?} because the response was already sent to the client"
+                );
+                return;
+            }
+            response_parts.status = http::StatusCode::FOUND;
+            response_parts
+                .headers
+                .insert(http::header::LOCATION, location);
+        }
+
+        /// Mark the response as sent to the client and get the status it is sent with. Any later calls to [`Self::set_status`] and
+        /// [`Self::redirect`] are ignored.
+        pub(crate) fn commit_response(&self) -> http::StatusCode {
+            let mut response_parts = self.response_parts_mut();
+            response_parts.extensions.insert(ResponseCommitted);
+            response_parts.status
+        }
+
+        /// Get all values that were written with [`Self::set_render_output`]
+        pub fn render_outputs(&self) -> RenderOutputs {
+            self.response_parts()
+                .extensions
+                .get::<RenderOutputs>()
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        /// Get a token that is cancelled when the current request is aborted. Long running server functions can wait on
+        /// [`CancellationToken::cancelled`](tokio_util::sync::CancellationToken::cancelled) or pass the token to other cancellable
+        /// operations to stop working once nobody is waiting for the result.
+        ///
+        /// The token is cancelled if the client disconnects before the server function returns or if a deadline set with
+        /// [`Self::set_deadline`] passes. Once a server function returns, the token is no longer cancelled when the client disconnects.
+        /// Streaming responses are dropped instead.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn expensive_report() -> Result<String, ServerFnError> {
+        ///     let token = server_context().cancellation_token();
+        ///     let mut report = String::new();
+        ///     for chunk in 0..1000 {
+        ///         if token.is_cancelled() {
+        ///             return Err(ServerFnError::new("The request was cancelled"));
+        ///         }
+        ///         report += &chunk.to_string();
+        ///     }
+        ///     Ok(report)
+        /// }
+        /// ```
+        pub fn cancellation_token(&self) -> tokio_util::sync::CancellationToken {
+            let mut parts = self.request_parts_mut();
+            if let Some(token) = parts
+                .extensions
+                .get::<tokio_util::sync::CancellationToken>()
+            {
+                return token.clone();
+            }
+            let token = tokio_util::sync::CancellationToken::new();
+            parts.extensions.insert(token.clone());
+            token
+        }
+
+        /// Get the sink that sends progress events of the current server function to the client. Events are only sent if the server
+        /// function streams its progress with [`progress_stream`](crate::prelude::progress_stream). Otherwise, they are dropped.
+        pub fn progress_sink(&self) -> crate::prelude::ProgressSink {
+            self.get::<crate::prelude::ProgressSink>()
+                .unwrap_or_default()
+        }
+
+        /// Cancel the [`Self::cancellation_token`] of the current request after `deadline`
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn set_deadline(&self, deadline: std::time::Duration) {
+            let token = self.cancellation_token();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(deadline) => token.cancel(),
+                    _ = token.cancelled() => {}
+                }
+            });
+        }
+
+        /// Get the address of the client that sent the current request. Proxy headers are only trusted as configured with
+        /// [`ServeConfigBuilder::client_ip_source`](crate::prelude::ServeConfigBuilder::client_ip_source). Returns `None` if the app
+        /// is not served with connect info.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn visit() -> Result<String, ServerFnError> {
+        ///     let ip = server_context().client_ip();
+        ///     Ok(format!("Hello {ip:?}"))
+        /// }
+        /// ```
+        #[cfg(feature = "axum")]
+        pub fn client_ip(&self) -> Option<std::net::IpAddr> {
+            self.handler_config()
+                .client_ip_source
+                .resolve(&self.request_parts())
+        }
+
+        /// Deserialize the `application/x-www-form-urlencoded` body of the current server function request, for example from a
+        /// plain form post of a page without javascript. The body is only buffered for server functions that are registered with
+        /// [`ServeConfigBuilder::read_server_fn_form`](crate::prelude::ServeConfigBuilder::read_server_fn_form). Forms larger than the
+        /// limit set with [`ServeConfigBuilder::max_form_body_size`](crate::prelude::ServeConfigBuilder::max_form_body_size) are
+        /// rejected with `413 Payload Too Large` before the server function runs.
+        ///
+        /// If the form can't be read, the response gets the status of the [`FormRejection`]: `415` for a request without a buffered
+        /// form body, `413` for a form that is too large and `422` for a form that doesn't match `T`.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[derive(serde::Deserialize)]
+        /// struct SignupForm {
+        ///     email: String,
+        ///     newsletter: Option<String>,
+        /// }
+        ///
+        /// #[server]
+        /// async fn signup() -> Result<(), ServerFnError> {
+        ///     let form: SignupForm = server_context().form()?;
+        ///     println!("{} signed up", form.email);
+        ///     Ok(())
+        /// }
+        ///
+        /// let mut cfg = dioxus::fullstack::Config::new();
+        ///
+        /// server_only! {
+        ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().read_server_fn_form::<Signup>());
+        /// }
+        /// ```
+        #[cfg(feature = "axum")]
+        pub fn form<T: serde::de::DeserializeOwned>(&self) -> Result<T, FormRejection> {
+            let result = match self.get::<FormBody>() {
+                Some(FormBody(body)) if body.len() > self.handler_config().max_form_body_size => {
+                    Err(FormRejection::TooLarge)
+                }
+                Some(FormBody(body)) => serde_urlencoded::from_bytes(&body)
+                    .map_err(|err| FormRejection::Invalid(err.to_string())),
+                None => {
+                    if crate::server::is_form_urlencoded(&self.request_parts().headers) {
+                        tracing::warn!("The form of a server function was read, but the server function is not registered with `ServeConfigBuilder::read_server_fn_form`");
+                    }
+                    Err(FormRejection::UnsupportedMediaType)
+                }
+            };
+            if let Err(rejection) = &result {
+                self.response_parts_mut().status = rejection.status();
+            }
+            result
+        }
+
+        /// Get the W3C trace context from the `traceparent` and `tracestate` headers of the current request. Forward it to the services
+        /// you call while handling the request so their spans join the trace of the caller.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn inventory() -> Result<String, ServerFnError> {
+        ///     let mut headers = http::HeaderMap::new();
+        ///     if let Some(context) = server_context().trace_context() {
+        ///         headers.insert("traceparent", context.traceparent(None).parse()?);
+        ///         if let Some(tracestate) = context.tracestate() {
+        ///             headers.insert("tracestate", tracestate.parse()?);
+        ///         }
+        ///     }
+        ///     // Send the headers with the request to the inventory service
+        ///     Ok(format!("{headers:?}"))
+        /// }
+        /// ```
+        pub fn trace_context(&self) -> Option<crate::prelude::TraceContext> {
+            crate::prelude::TraceContext::from_headers(&self.request_parts().headers)
+        }
+
+        /// Get the host of the current request from the authority of the uri or the `Host` header. The host is normalized if
+        /// [`ServeConfigBuilder::host_normalization`](crate::prelude::ServeConfigBuilder::host_normalization) is set.
+        #[cfg(feature = "axum")]
+        pub fn host(&self) -> Option<String> {
+            let parts = self.request_parts();
+            if let Some(authority) = parts.uri.authority() {
+                return Some(authority.to_string());
+            }
+            parts
+                .headers
+                .get(http::header::HOST)
+                .and_then(|host| host.to_str().ok())
+                .map(String::from)
+        }
+
+        /// Get the locale that was negotiated for the current request from the `Accept-Language` header and the locales set with
+        /// [`ServeConfigBuilder::locales`](crate::prelude::ServeConfigBuilder::locales).
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[component]
+        /// fn Greeting() -> Element {
+        ///     let mut greeting = "Hello";
+        ///     server_only! {
+        ///         if server_context().locale().as_deref() == Some("fr") {
+        ///             greeting = "Bonjour";
+        ///         }
+        ///     }
+        ///     rsx! { "{greeting}" }
+        /// }
+        /// ```
+        pub fn locale(&self) -> Option<String> {
+            self.request_parts()
+                .extensions
+                .get::<Locale>()
+                .map(|locale| locale.0.clone())
+        }
+
+        /// Set the locale of the current request. This is read back with [`Self::locale`].
+        pub fn set_locale(&self, locale: impl Into<String>) {
+            self.request_parts_mut()
+                .extensions
+                .insert(Locale(locale.into()));
+        }
+
+        /// Get the color theme of the current request if [`ServeConfigBuilder::theme_cookie`](crate::prelude::ServeConfigBuilder::theme_cookie)
+        /// is set. Components should prefer [`use_theme`](crate::prelude::use_theme) which also works on the client.
+        pub fn theme(&self) -> Option<String> {
+            self.request_parts()
+                .extensions
+                .get::<crate::theme::Theme>()
+                .map(|theme| theme.0.clone())
+        }
+
+        /// Set the color theme of the current request. This is read back with [`Self::theme`].
+        pub fn set_theme(&self, theme: impl Into<String>) {
+            self.request_parts_mut()
+                .extensions
+                .insert(crate::theme::Theme(theme.into()));
+        }
+
+        /// Get the nonce of the current request if [`ServeConfigBuilder::csp_nonce`](crate::prelude::ServeConfigBuilder::csp_nonce) is
+        /// enabled. Every `<script>` and `<style>` element in the page gets the nonce automatically, but components can use it for
+        /// other resources the content security policy allows with a nonce.
+        pub fn nonce(&self) -> Option<String> {
+            self.request_parts()
+                .extensions
+                .get::<Nonce>()
+                .map(|nonce| nonce.0.clone())
+        }
+
+        /// Set the nonce of the current request. This is read back with [`Self::nonce`].
+        pub fn set_nonce(&self, nonce: impl Into<String>) {
+            self.request_parts_mut()
+                .extensions
+                .insert(Nonce(nonce.into()));
+        }
+
+        /// Allow `source` in a directive of the content security policy of the current page only. Use this for pages that embed
+        /// third-party widgets instead of loosening the policy of every page. The source is merged into the policy from
+        /// [`ServeConfigBuilder::content_security_policy`](crate::prelude::ServeConfigBuilder::content_security_policy) or the
+        /// `Content-Security-Policy` header of the response. Sources that are already allowed are skipped, and the nonce of the page is kept.
+        ///
+        /// If the policy doesn't have the directive, it is created from the `default-src` directive so the rest of the policy still applies.
+        /// Without either directive, the type of resource is not restricted and nothing is added.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[component]
+        /// fn Map() -> Element {
+        ///     server_only! {
+        ///         server_context().allow_csp_source("frame-src", "https://maps.example.com");
+        ///     }
+        ///     rsx! { iframe { src: "https://maps.example.com/embed" } }
+        /// }
+        /// ```
+        pub fn allow_csp_source(&self, directive: impl Into<String>, source: impl Into<String>) {
+            let source = (directive.into().to_ascii_lowercase(), source.into());
+            let mut parts = self.request_parts_mut();
+            match parts.extensions.get_mut::<CspSources>() {
+                Some(CspSources(sources)) => {
+                    if !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
+                None => {
+                    parts.extensions.insert(CspSources(vec![source]));
+                }
+            }
+        }
+
+        /// Set the `X-Robots-Tag` header of the current response, for example to `noindex` to keep a page out of search results. This
+        /// replaces the directive from [`ServeConfigBuilder::robots`](crate::prelude::ServeConfigBuilder::robots). It also works in
+        /// server functions and other responses that are not html.
+        ///
+        /// Like other headers, the directive is only sent if it is set before the status and headers of a page are sent. Pages served
+        /// from the incremental cache are not rendered, so only the directive from the config applies to them.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[component]
+        /// fn Draft() -> Element {
+        ///     server_only! {
+        ///         server_context().set_robots("noindex");
+        ///     }
+        ///     rsx! { "This post is not published yet" }
+        /// }
+        /// ```
+        pub fn set_robots(&self, directive: impl AsRef<str>) {
+            match http::HeaderValue::from_str(directive.as_ref()) {
+                Ok(directive) => {
+                    self.response_parts_mut()
+                        .headers
+                        .insert(X_ROBOTS_TAG, directive);
+                }
+                Err(err) => tracing::error!("Invalid X-Robots-Tag directive: {err}"),
+            }
+        }
+
+        /// Allow a feature of the [`ServeConfigBuilder::permissions_policy`](crate::prelude::ServeConfigBuilder::permissions_policy) for
+        /// `origin` on the current page only. The origin is `"self"`, `"*"` or an origin like `"https://example.com"`. Without a
+        /// permissions policy in the config, no features are restricted and this has no effect.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[component]
+        /// fn StoreLocator() -> Element {
+        ///     server_only! {
+        ///         server_context().allow_permission("geolocation", "self");
+        ///     }
+        ///     rsx! { "Find a store near you" }
+        /// }
+        /// ```
+        pub fn allow_permission(&self, feature: impl Into<String>, origin: impl Into<String>) {
+            let permission = (feature.into(), origin.into());
+            let mut parts = self.request_parts_mut();
+            match parts.extensions.get_mut::<Permissions>() {
+                Some(Permissions(permissions)) => permissions.push(permission),
+                None => {
+                    parts.extensions.insert(Permissions(vec![permission]));
+                }
+            }
+        }
+
+        /// Get the permissions policy of the current page with the features that were allowed with [`Self::allow_permission`]
+        pub(crate) fn permissions_policy(
+            &self,
+            policy: &crate::PermissionsPolicy,
+        ) -> crate::PermissionsPolicy {
+            let mut policy = policy.clone();
+            if let Some(Permissions(permissions)) = self.request_parts().extensions.get() {
+                for (feature, origin) in permissions {
+                    policy = policy.allow(feature.clone(), [origin.clone()]);
+                }
+            }
+            policy
+        }
+
+        /// Get the settings of the handler that created this context
+        #[cfg(feature = "axum")]
+        pub(crate) fn handler_config(&self) -> Arc<crate::serve_config::HandlerConfig> {
+            self.get().unwrap_or_default()
+        }
+
+        /// Get the directives and sources that were added to the content security policy of the current page with [`Self::allow_csp_source`]
+        pub(crate) fn csp_sources(&self) -> Vec<(String, String)> {
+            self.request_parts()
+                .extensions
+                .get::<CspSources>()
+                .map(|sources| sources.0.clone())
+                .unwrap_or_default()
+        }
+
+        /// Set the route pattern that matched the current request. This is read back with [`Self::route_pattern`].
+        pub fn set_route_pattern(&self, pattern: impl Into<String>) {
+            self.request_parts_mut()
+                .extensions
+                .insert(RoutePattern(pattern.into()));
+        }
+    }
+
+    /// The route pattern that matched a request, stored in the request extensions
+    #[derive(Clone)]
+    struct RoutePattern(String);
+
+    /// The negotiated locale of a request, stored in the request extensions
+    #[derive(Clone)]
+    struct Locale(String);
+
+    /// The content security policy nonce of a request, stored in the request extensions
+    #[derive(Clone)]
+    struct Nonce(String);
+
+    /// The sources that were added to the content security policy of a page, stored in the request extensions
+    #[derive(Clone)]
+    struct CspSources(Vec<(String, String)>);
+
+    /// The features that were allowed in the permissions policy of a page, stored in the request extensions
+    #[derive(Clone)]
+    struct Permissions(Vec<(String, String)>);
+
+    /// A marker that the status and headers of the response were sent, stored in the response extensions
+    #[derive(Clone)]
+    struct ResponseCommitted;
+}
+
+/// Typed values that components wrote during a render with [`DioxusServerContext::set_render_output`]
+#[derive(Clone, Default, Debug)]
+pub struct RenderOutputs {
+    values: http::Extensions,
+}
+
+impl RenderOutputs {
+    /// Get the output of type `T` if one was set
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.values.get::<T>()
     }
 }
 
+/// The buffered form-urlencoded body of a server function request. See [`DioxusServerContext::form`]
+#[cfg(feature = "axum")]
+#[derive(Clone)]
+pub(crate) struct FormBody(pub(crate) axum::body::Bytes);
+
+/// An error that occurs when the form of a request can't be read with [`DioxusServerContext::form`]
+#[cfg(feature = "axum")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormRejection {
+    /// The request does not have an `application/x-www-form-urlencoded` body
+    UnsupportedMediaType,
+    /// The form is larger than the limit set with
+    /// [`ServeConfigBuilder::max_form_body_size`](crate::prelude::ServeConfigBuilder::max_form_body_size)
+    TooLarge,
+    /// The form is missing fields or has fields with the wrong type
+    Invalid(String),
+}
+
+#[cfg(feature = "axum")]
+impl FormRejection {
+    /// Get the status of the response for the rejection
+    pub fn status(&self) -> http::StatusCode {
+        match self {
+            Self::UnsupportedMediaType => http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::TooLarge => http::StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Invalid(_) => http::StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl std::fmt::Display for FormRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedMediaType => {
+                write!(f, "Expected an application/x-www-form-urlencoded body")
+            }
+            Self::TooLarge => write!(f, "The form is too large"),
+            Self::Invalid(err) => write!(f, "Invalid form: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl std::error::Error for FormRejection {}
+
+/// The header that tells search engines how to index a response
+pub(crate) const X_ROBOTS_TAG: http::HeaderName = http::HeaderName::from_static("x-robots-tag");
+
+/// The HTTP caching policy a server function set for its response with [`DioxusServerContext::cache_response_for`] or
+/// [`DioxusServerContext::cache_response_publicly_for`]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ServerFnCachePolicy {
+    pub(crate) ttl: std::time::Duration,
+    /// If the response is the same for every caller
+    pub(crate) public: bool,
+}
+
 #[test]
 fn server_context_as_any_map() {
     let parts = http::Request::new(()).into_parts().0;
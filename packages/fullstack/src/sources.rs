@@ -0,0 +1,73 @@
+//! Sources of time and randomness used by the server. Features like nonces and tokens draw from these sources
+//! instead of the system directly so they can be replaced with deterministic sources in tests.
+
+use std::time::SystemTime;
+
+/// A source of the current time.
+///
+/// # Example
+/// ```rust
+/// use dioxus_fullstack::TimeSource;
+/// use std::time::{Duration, SystemTime};
+///
+/// /// A clock that is always stuck at the same time
+/// struct FixedTime(SystemTime);
+///
+/// impl TimeSource for FixedTime {
+///     fn now(&self) -> SystemTime {
+///         self.0
+///     }
+/// }
+///
+/// let clock = FixedTime(SystemTime::UNIX_EPOCH + Duration::from_secs(60));
+/// assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + Duration::from_secs(60));
+/// ```
+pub trait TimeSource: Send + Sync + 'static {
+    /// Get the current time
+    fn now(&self) -> SystemTime;
+}
+
+/// A source of random bytes.
+///
+/// # Example
+/// ```rust
+/// use dioxus_fullstack::RandSource;
+///
+/// /// A random source that always returns the same bytes
+/// struct FixedRand(u8);
+///
+/// impl RandSource for FixedRand {
+///     fn fill_bytes(&self, dest: &mut [u8]) {
+///         dest.fill(self.0);
+///     }
+/// }
+///
+/// let mut bytes = [0; 4];
+/// FixedRand(7).fill_bytes(&mut bytes);
+/// assert_eq!(bytes, [7; 4]);
+/// ```
+pub trait RandSource: Send + Sync + 'static {
+    /// Fill `dest` with random bytes
+    fn fill_bytes(&self, dest: &mut [u8]);
+}
+
+/// The default [`TimeSource`] which reads the system clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// The default [`RandSource`] which uses a cryptographically secure random number generator seeded by the operating system
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsRandSource;
+
+impl RandSource for OsRandSource {
+    fn fill_bytes(&self, dest: &mut [u8]) {
+        use rand::RngCore;
+        rand::thread_rng().fill_bytes(dest);
+    }
+}
@@ -70,7 +70,9 @@ impl<E> StreamingRenderer<E> {
         mut render_into: Sender<Result<String, E>>,
     ) -> Self {
         let start_html = before_body.to_string();
-        _ = render_into.start_send(Ok(start_html));
+        if !start_html.is_empty() {
+            _ = render_into.start_send(Ok(start_html));
+        }
 
         Self {
             channel: render_into.into(),
@@ -142,3 +144,89 @@ impl Display for Mount {
         write!(f, "{}", self.id)
     }
 }
+
+/// A stream of html chunks that ends with a marker if it runs longer than the [`StreamTimeouts`](crate::StreamTimeouts) of the page
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct TimeoutStream<S> {
+    inner: Option<S>,
+    total: Option<std::pin::Pin<Box<tokio::time::Sleep>>>,
+    idle: Option<(std::time::Duration, std::pin::Pin<Box<tokio::time::Sleep>>)>,
+    marker: Option<String>,
+    route: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> TimeoutStream<S> {
+    /// Wrap a stream that is cut off with `marker` when a timeout is hit. The timeouts start now
+    pub(crate) fn new(
+        inner: S,
+        timeouts: crate::StreamTimeouts,
+        marker: String,
+        route: String,
+    ) -> Self {
+        Self {
+            inner: Some(inner),
+            total: timeouts
+                .total
+                .map(|total| Box::pin(tokio::time::sleep(total))),
+            idle: timeouts
+                .idle
+                .map(|idle| (idle, Box::pin(tokio::time::sleep(idle)))),
+            marker: Some(marker),
+            route,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S, E> futures_util::Stream for TimeoutStream<S>
+where
+    S: futures_util::Stream<Item = Result<String, E>> + Unpin,
+{
+    type Item = Result<String, E>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use futures_util::StreamExt;
+        use std::future::Future;
+        use std::task::Poll;
+
+        let this = &mut *self;
+        let Some(inner) = &mut this.inner else {
+            return Poll::Ready(None);
+        };
+        // Poll the total timeout before the stream so it is hit and registers its waker even if the stream is always ready
+        let timeout = if this
+            .total
+            .as_mut()
+            .is_some_and(|sleep| sleep.as_mut().poll(cx).is_ready())
+        {
+            "total"
+        } else {
+            if let Poll::Ready(chunk) = inner.poll_next_unpin(cx) {
+                if let Some((idle, sleep)) = &mut this.idle {
+                    sleep.as_mut().reset(tokio::time::Instant::now() + *idle);
+                }
+                return Poll::Ready(chunk);
+            }
+            if this
+                .idle
+                .as_mut()
+                .is_some_and(|(_, sleep)| sleep.as_mut().poll(cx).is_ready())
+            {
+                "idle"
+            } else {
+                return Poll::Pending;
+            }
+        };
+        tracing::warn!(
+            "Ended the stream of route \"{}\" after its {timeout} timeout. Pending suspense boundaries were not sent",
+            this.route
+        );
+        // Dropping the stream cancels the render
+        this.inner = None;
+        Poll::Ready(this.marker.take().map(Ok))
+    }
+}
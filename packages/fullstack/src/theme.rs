@@ -0,0 +1,47 @@
+//! The color theme of a page that the server reads from a cookie. See [`ServeConfigBuilder::theme_cookie`](crate::prelude::ServeConfigBuilder::theme_cookie)
+//!
+//! On the server, the theme is provided to the root of the app and written to the `data-theme` attribute of the `<html>` element.
+//! The client reads the theme back from the attribute so it hydrates with the same theme the server rendered.
+
+use dioxus_lib::prelude::{try_consume_context, use_hook};
+
+/// The attribute of the `<html>` element the theme is written to
+#[allow(unused)]
+pub(crate) const THEME_ATTRIBUTE: &str = "data-theme";
+
+/// The theme of the current request, provided to the root of the app
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Theme(pub(crate) String);
+
+/// Get the color theme the page was rendered with. On the server, this is the theme of the current request. On the client, this is
+/// the theme the server wrote into the page during the initial render. Returns `None` if no theme cookie is configured.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app() -> Element {
+///     let theme = use_theme();
+///     let icon = if theme.as_deref() == Some("dark") { "🌙" } else { "☀️" };
+///
+///     rsx! { button { "{icon}" } }
+/// }
+/// ```
+pub fn use_theme() -> Option<String> {
+    use_hook(|| {
+        if let Some(Theme(theme)) = try_consume_context::<Theme>() {
+            return Some(theme);
+        }
+
+        #[cfg(all(feature = "web", not(feature = "server")))]
+        {
+            let document = web_sys::window()?.document()?;
+            document.document_element()?.get_attribute(THEME_ATTRIBUTE)
+        }
+        #[cfg(not(all(feature = "web", not(feature = "server"))))]
+        {
+            None
+        }
+    })
+}
@@ -0,0 +1,142 @@
+//! W3C trace context propagation. See [`DioxusServerContext::trace_context`](crate::prelude::DioxusServerContext::trace_context)
+
+/// The header that carries the trace and parent span ids
+pub(crate) const TRACEPARENT: &str = "traceparent";
+/// The header that carries vendor specific trace state
+pub(crate) const TRACESTATE: &str = "tracestate";
+
+/// The [W3C trace context](https://www.w3.org/TR/trace-context/) of a request, parsed from the `traceparent` and `tracestate` headers.
+///
+/// Forward the context to other services with [`Self::traceparent`] and [`Self::tracestate`] so their spans join the same trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: String,
+    parent_id: String,
+    flags: u8,
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Parse the trace context from the `traceparent` and `tracestate` headers of a request. Returns `None` if there is no valid
+    /// `traceparent` header.
+    pub fn from_headers(headers: &http::HeaderMap) -> Option<Self> {
+        let traceparent = headers.get(TRACEPARENT)?.to_str().ok()?;
+        let tracestate = headers
+            .get_all(TRACESTATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut context = Self::parse(traceparent)?;
+        context.tracestate = (!tracestate.is_empty()).then_some(tracestate);
+        Some(context)
+    }
+
+    /// Parse a `traceparent` header
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+        // Version 00 has exactly four fields. Later versions may add more fields after the flags
+        if !is_lower_hex(version, 2)
+            || version == "ff"
+            || (version == "00" && parts.next().is_some())
+        {
+            return None;
+        }
+        if !is_lower_hex(trace_id, 32) || trace_id.bytes().all(|byte| byte == b'0') {
+            return None;
+        }
+        if !is_lower_hex(parent_id, 16) || parent_id.bytes().all(|byte| byte == b'0') {
+            return None;
+        }
+        if !is_lower_hex(flags, 2) {
+            return None;
+        }
+
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            flags: u8::from_str_radix(flags, 16).ok()?,
+            tracestate: None,
+        })
+    }
+
+    /// Get the id of the trace as 32 lowercase hex characters
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// Get the id of the span in the calling service as 16 lowercase hex characters
+    pub fn parent_id(&self) -> &str {
+        &self.parent_id
+    }
+
+    /// Check if the calling service recorded the trace
+    pub fn sampled(&self) -> bool {
+        self.flags & 1 == 1
+    }
+
+    /// Get the vendor specific trace state
+    pub fn tracestate(&self) -> Option<&str> {
+        self.tracestate.as_deref()
+    }
+
+    /// Get the `traceparent` header to send to other services. Pass the id of your own span as `span_id` so the spans of the other
+    /// service become children of it, or `None` to keep the parent of the incoming request.
+    pub fn traceparent(&self, span_id: Option<u64>) -> String {
+        let parent_id = match span_id {
+            Some(span_id) => format!("{span_id:016x}"),
+            None => self.parent_id.clone(),
+        };
+        format!("00-{}-{parent_id}-{:02x}", self.trace_id, self.flags)
+    }
+}
+
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Record the trace and parent span ids of a request on a span with empty `trace_id` and `parent_span_id` fields. Subscribers that export
+/// to OpenTelemetry can use the fields to connect the span to the trace of the caller.
+#[cfg(feature = "axum")]
+pub(crate) fn record_trace_context(span: &tracing::Span, headers: &http::HeaderMap) {
+    if let Some(context) = TraceContext::from_headers(headers) {
+        span.record("trace_id", context.trace_id());
+        span.record("parent_span_id", context.parent_id());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_traceparent() {
+        let context =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(context.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert!(context.sampled());
+        assert_eq!(
+            context.traceparent(Some(0xff)),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00000000000000ff-01"
+        );
+
+        // All zero ids and extra fields in version 00 are invalid
+        assert!(
+            TraceContext::parse("00-00000000000000000000000000000000-00f067aa0ba902b7-01")
+                .is_none()
+        );
+        assert!(
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Component variants that are chosen on the server for each request and sent to the client with the hydration data.
+//!
+//! On the server, the variants are assigned with [`ServeConfigBuilder::variants`](crate::prelude::ServeConfigBuilder::variants)
+//! before the render starts and provided to the root of the app. The assignment is serialized into the page next to the rest of
+//! the hydration data so the client renders the same variant and the wrong variant never flashes on the screen.
+
+use base64::Engine;
+use dioxus_lib::prelude::{try_consume_context, use_hook, ScopeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The id of the element the variants are serialized into
+#[allow(unused)]
+pub(crate) const VARIANTS_ID: &str = "dioxus-variants";
+
+/// The variant of each feature flag that was assigned to the current request.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app() -> Element {
+///     let variants = use_variants();
+///     match variants.get("checkout") {
+///         Some("one-page") => rsx! { "One page checkout" },
+///         _ => rsx! { "Classic checkout" },
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Variants {
+    assignments: Arc<HashMap<String, String>>,
+}
+
+impl Variants {
+    /// Create a new set of variants without any assignments
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assign a variant to a feature flag. This will replace any existing variant for the same flag.
+    pub fn with(mut self, flag: impl Into<String>, variant: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.assignments).insert(flag.into(), variant.into());
+        self
+    }
+
+    /// Get the variant that was assigned to a feature flag
+    pub fn get(&self, flag: &str) -> Option<&str> {
+        self.assignments.get(flag).map(String::as_str)
+    }
+
+    /// Check if no variants were assigned
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// Pick one of `variants` for a feature flag from a stable key like a user id. The same key always gets the same variant of a
+    /// flag, in every request and on every server. Different flags are bucketed independently. Returns `None` if `variants` is empty.
+    ///
+    /// ```rust
+    /// # use dioxus_fullstack::prelude::*;
+    /// let first = Variants::bucket("user-42", "checkout", &["classic", "one-page"]);
+    /// let second = Variants::bucket("user-42", "checkout", &["classic", "one-page"]);
+    /// assert_eq!(first, second);
+    /// ```
+    pub fn bucket<'a>(key: &str, flag: &str, variants: &[&'a str]) -> Option<&'a str> {
+        if variants.is_empty() {
+            return None;
+        }
+        // FNV-1a is stable across processes and versions of rust, unlike the default hasher
+        let hash = flag
+            .bytes()
+            .chain(std::iter::once(0))
+            .chain(key.bytes())
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        Some(variants[(hash % variants.len() as u64) as usize])
+    }
+
+    /// Encode the variants as base64. This is intended to be used in the server to send the variants to the client. Returns `None`
+    /// and logs the error if the variants fail to serialize.
+    #[cfg(feature = "server")]
+    pub(crate) fn serialized(&self) -> Option<String> {
+        let mut serialized = Vec::new();
+        if let Err(err) = ciborium::into_writer(&*self.assignments, &mut serialized) {
+            tracing::error!("Failed to serialize the variants: {err}");
+            return None;
+        }
+        Some(base64::engine::general_purpose::STANDARD.encode(serialized))
+    }
+
+    /// Decode variants that were serialized on the server with [`Self::serialized`]
+    #[allow(unused)]
+    pub(crate) fn deserialize(data: &str) -> Option<Self> {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .ok()?;
+        let assignments: HashMap<String, String> =
+            ciborium::from_reader(decoded.as_slice()).ok()?;
+        Some(Self {
+            assignments: Arc::new(assignments),
+        })
+    }
+
+    /// Read the variants the server serialized into the page
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    fn from_document() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let element = document.get_element_by_id(VARIANTS_ID)?;
+        Self::deserialize(&element.text_content()?)
+    }
+}
+
+/// Get the [`Variants`] for the current app. On the server, these are the variants assigned to the current request. On the client,
+/// these are the variants the server serialized into the page during the initial render.
+pub fn use_variants() -> Variants {
+    use_hook(variants)
+}
+
+pub(crate) fn variants() -> Variants {
+    if let Some(variants) = try_consume_context::<Variants>() {
+        return variants;
+    }
+
+    #[allow(unused_mut)]
+    let mut variants = Variants::default();
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    if let Some(from_document) = Variants::from_document() {
+        variants = from_document;
+    }
+
+    ScopeId::ROOT.provide_context(variants)
+}
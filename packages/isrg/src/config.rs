@@ -4,13 +4,17 @@
 use crate::fs_cache::PathMapFn;
 
 use crate::memory_cache::InMemoryCache;
-use crate::IncrementalRenderer;
+use crate::{CacheError, IncrementalRenderer};
 
 use std::{
     path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 
+/// A callback that is run when a render can't be written to the cache
+pub(crate) type CacheErrorFn = Arc<dyn Fn(&CacheError) + Send + Sync>;
+
 /// A configuration for the incremental renderer.
 #[derive(Clone)]
 pub struct IncrementalRendererConfig {
@@ -19,9 +23,15 @@ pub struct IncrementalRendererConfig {
     invalidate_after: Option<Duration>,
     clear_cache: bool,
     pre_render: bool,
+    normalize_query: bool,
+    on_cache_error: Option<CacheErrorFn>,
 
     #[cfg(not(target_arch = "wasm32"))]
     map_path: Option<PathMapFn>,
+    #[cfg(not(target_arch = "wasm32"))]
+    max_file_age: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    build_version: Option<String>,
 }
 
 impl Default for IncrementalRendererConfig {
@@ -39,8 +49,14 @@ impl IncrementalRendererConfig {
             invalidate_after: None,
             clear_cache: true,
             pre_render: false,
+            normalize_query: false,
+            on_cache_error: None,
             #[cfg(not(target_arch = "wasm32"))]
             map_path: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            max_file_age: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            build_version: None,
         }
     }
 
@@ -54,7 +70,35 @@ impl IncrementalRendererConfig {
     /// The function should return the path to the folder to store the index.html file in.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn map_path<F: Fn(&str) -> PathBuf + Send + Sync + 'static>(mut self, map_path: F) -> Self {
-        self.map_path = Some(std::sync::Arc::new(map_path));
+        self.map_path = Some(Arc::new(map_path));
+        self
+    }
+
+    /// Remove files in the static directory that are older than `max_file_age` from a background thread. This keeps the disk usage
+    /// of the file system cache bounded when the cache is not cleared on startup. The thread stops when the renderer is dropped.
+    ///
+    /// Only files in the static directory are removed, so renders written to other folders with [`Self::map_path`] are kept. Renders
+    /// that are already in the memory cache are kept until they are invalidated with [`Self::invalidate_after`] or evicted.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn max_file_age(mut self, max_file_age: Duration) -> Self {
+        self.max_file_age = Some(max_file_age);
+        self
+    }
+
+    /// Set the version of the build the renders are created with, for example the git commit of the deploy. The version is stored in
+    /// the static directory. If the cache was written by a different version, the file system cache is cleared when the renderer is built,
+    /// so a new deploy never serves renders of the old one.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus_isrg::IncrementalRenderer;
+    /// let renderer = IncrementalRenderer::builder()
+    ///     .clear_cache(false)
+    ///     .build_version(env!("CARGO_PKG_VERSION"))
+    ///     .build();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_version(mut self, build_version: impl Into<String>) -> Self {
+        self.build_version = Some(build_version.into());
         self
     }
 
@@ -82,6 +126,38 @@ impl IncrementalRendererConfig {
         self
     }
 
+    /// Normalize the query string of routes before they are used as cache keys (default: false). Parameters are sorted by name
+    /// and exact duplicates are removed, so routes with equivalent query strings share one cache entry. Values of a parameter
+    /// that appears more than once keep their order.
+    ///
+    /// ```rust
+    /// # use dioxus_isrg::IncrementalRenderer;
+    /// let mut renderer = IncrementalRenderer::builder().normalize_query(true).build();
+    /// renderer.cache("/search?b=2&a=1".to_string(), "results").unwrap();
+    /// assert!(renderer.get("/search?a=1&a=1&b=2").unwrap().is_some());
+    /// ```
+    pub fn normalize_query(mut self, normalize_query: bool) -> Self {
+        self.normalize_query = normalize_query;
+        self
+    }
+
+    /// Set a callback that is run when a render can't be written to the cache, for example because the disk is full. Failed writes
+    /// are always logged as a warning. The page is still sent to the client, so without a callback the failures are easy to miss.
+    ///
+    /// ```rust
+    /// # use dioxus_isrg::IncrementalRenderer;
+    /// let renderer = IncrementalRenderer::builder()
+    ///     .on_cache_error(|error| eprintln!("Failed to cache {}: {}", error.route(), error.error()))
+    ///     .build();
+    /// ```
+    pub fn on_cache_error(
+        mut self,
+        on_cache_error: impl Fn(&CacheError) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_cache_error = Some(Arc::new(on_cache_error));
+        self
+    }
+
     /// Build the incremental renderer.
     pub fn build(self) -> IncrementalRenderer {
         let mut renderer = IncrementalRenderer {
@@ -93,12 +169,30 @@ impl IncrementalRendererConfig {
             ),
             memory_cache: InMemoryCache::new(self.memory_cache_limit, self.invalidate_after),
             invalidate_after: self.invalidate_after,
+            normalize_query: self.normalize_query,
+            on_cache_error: self.on_cache_error,
+            #[cfg(not(target_arch = "wasm32"))]
+            janitor: Arc::new(()),
         };
 
         if self.clear_cache {
             renderer.invalidate_all();
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(build_version) = &self.build_version {
+                crate::fs_cache::check_build_version(&self.static_dir, build_version);
+            }
+            if let Some(max_file_age) = self.max_file_age {
+                crate::fs_cache::spawn_janitor(
+                    self.static_dir.clone(),
+                    max_file_age,
+                    Arc::downgrade(&renderer.janitor),
+                );
+            }
+        }
+
         renderer
     }
 }
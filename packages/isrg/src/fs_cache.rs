@@ -3,7 +3,11 @@
 use chrono::{DateTime, Utc};
 
 use super::{IncrementalRendererError, RenderFreshness};
-use std::{path::PathBuf, sync::Arc, time::SystemTime};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+    time::SystemTime,
+};
 
 pub(crate) type PathMapFn = Arc<dyn Fn(&str) -> PathBuf + Send + Sync>;
 
@@ -40,9 +44,11 @@ impl FileSystemCache {
         route: String,
         timestamp: DateTime<Utc>,
         data: Vec<u8>,
+        preloads: &[String],
     ) -> Result<(), IncrementalRendererError> {
         use std::io::Write;
         let file_path = self.route_as_path(&route, timestamp);
+        let preloads_path = preloads_path(&file_path);
         if let Some(parent) = file_path.parent() {
             if !parent.exists() {
                 std::fs::create_dir_all(parent)?;
@@ -51,6 +57,14 @@ impl FileSystemCache {
         let file = std::fs::File::create(file_path)?;
         let mut file = std::io::BufWriter::new(file);
         file.write_all(&data)?;
+        // Dropping the writer ignores errors, so flush explicitly to catch failures like a full disk
+        file.flush()?;
+        // Store the preload links next to the render so cache hits don't need to read the render to find them
+        if preloads.is_empty() {
+            let _ = std::fs::remove_file(preloads_path);
+        } else {
+            std::fs::write(preloads_path, preloads.join("\n"))?;
+        }
         Ok(())
     }
 
@@ -61,6 +75,7 @@ impl FileSystemCache {
 
     pub fn invalidate(&mut self, route: &str) {
         let file_path = self.find_file(route).unwrap().full_path;
+        let _ = std::fs::remove_file(preloads_path(&file_path));
         if let Err(err) = std::fs::remove_file(file_path) {
             tracing::error!("Failed to remove file: {}", err);
         }
@@ -69,15 +84,18 @@ impl FileSystemCache {
     pub fn get(
         &self,
         route: &str,
-    ) -> Result<Option<(RenderFreshness, Vec<u8>)>, IncrementalRendererError> {
+    ) -> Result<Option<(RenderFreshness, Vec<u8>, Vec<String>)>, IncrementalRendererError> {
         if let Some(file_path) = self.find_file(route) {
             if let Some(freshness) = file_path.freshness(self.invalidate_after) {
-                if let Ok(file) = std::fs::File::open(file_path.full_path) {
+                if let Ok(file) = std::fs::File::open(&file_path.full_path) {
                     let mut file = std::io::BufReader::new(file);
                     let mut cache_hit = Vec::new();
                     std::io::copy(&mut file, &mut cache_hit)?;
+                    let preloads = std::fs::read_to_string(preloads_path(&file_path.full_path))
+                        .map(|preloads| preloads.lines().map(String::from).collect())
+                        .unwrap_or_default();
                     tracing::trace!("file cache hit {:?}", route);
-                    return Ok(Some((freshness, cache_hit)));
+                    return Ok(Some((freshness, cache_hit, preloads)));
                 }
             }
         }
@@ -100,6 +118,7 @@ impl FileSystemCache {
                             }
                         }
                         // if the timestamp is invalid or passed, delete the file
+                        let _ = std::fs::remove_file(preloads_path(&cached_path.full_path));
                         if let Err(err) = std::fs::remove_file(entry.path()) {
                             tracing::error!("Failed to remove file: {}", err);
                         }
@@ -162,6 +181,84 @@ impl ValidCachedPath {
     }
 }
 
+/// The file in the static directory that stores the version of the build that wrote the cache
+const BUILD_VERSION_FILE: &str = ".dioxus-build-version";
+
+/// Clear the file system cache if it was written by a different version of the build, then store the current version
+pub(crate) fn check_build_version(static_dir: &Path, build_version: &str) {
+    let version_path = static_dir.join(BUILD_VERSION_FILE);
+    let cached_version = std::fs::read_to_string(&version_path).ok();
+    if cached_version.as_deref() == Some(build_version) {
+        return;
+    }
+    if cached_version.is_some() {
+        tracing::info!(
+            "Clearing the incremental cache that was written by the build {cached_version:?}"
+        );
+    }
+    let _ = std::fs::remove_dir_all(static_dir);
+    let written = std::fs::create_dir_all(static_dir)
+        .and_then(|_| std::fs::write(&version_path, build_version));
+    if let Err(err) = written {
+        tracing::error!("Failed to write the build version of the incremental cache: {err}");
+    }
+}
+
+/// Spawn a thread that removes renders older than `max_age` from the static directory until `alive` is dropped
+pub(crate) fn spawn_janitor(static_dir: PathBuf, max_age: std::time::Duration, alive: Weak<()>) {
+    let interval = (max_age / 2).max(std::time::Duration::from_secs(1));
+    let spawned = std::thread::Builder::new()
+        .name("dioxus-isrg-janitor".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(interval);
+            if alive.upgrade().is_none() {
+                return;
+            }
+            remove_old_files(&static_dir, max_age);
+        });
+    if let Err(err) = spawned {
+        tracing::error!("Failed to spawn the incremental cache janitor: {err}");
+    }
+}
+
+/// Remove the renders and their preload links that were last modified more than `max_age` ago
+fn remove_old_files(dir: &Path, max_age: std::time::Duration) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            remove_old_files(&path, max_age);
+            continue;
+        }
+        let is_render = matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("html" | "links")
+        );
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if is_render && expired {
+            tracing::trace!("Removing expired render {path:?}");
+            if let Err(err) = std::fs::remove_file(&path) {
+                tracing::error!("Failed to remove file: {}", err);
+            }
+        }
+    }
+}
+
+/// The path of the file the preload links of a cached render are stored in
+fn preloads_path(render_path: &std::path::Path) -> PathBuf {
+    render_path.with_extension("links")
+}
+
 fn decode_timestamp(timestamp: &str) -> Option<std::time::SystemTime> {
     let timestamp = u64::from_str_radix(timestamp, 16).ok()?;
     Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(timestamp))
@@ -8,9 +8,10 @@ mod freshness;
 mod fs_cache;
 mod memory_cache;
 
+use std::borrow::Cow;
 use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 pub use config::*;
 pub use freshness::*;
 
@@ -24,6 +25,8 @@ pub struct CachedRender<'a> {
     pub freshness: RenderFreshness,
     /// The rendered response
     pub response: &'a [u8],
+    /// The values of the `Link` preload headers that were computed when the response was rendered
+    pub preloads: &'a [String],
 }
 
 /// An incremental renderer.
@@ -32,6 +35,13 @@ pub struct IncrementalRenderer {
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) file_system_cache: fs_cache::FileSystemCache,
     invalidate_after: Option<Duration>,
+    normalize_query: bool,
+    #[cfg_attr(target_arch = "wasm32", allow(unused))]
+    on_cache_error: Option<config::CacheErrorFn>,
+    /// The thread that removes old files from the file system cache stops once this is dropped
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(unused)]
+    janitor: std::sync::Arc<()>,
 }
 
 impl IncrementalRenderer {
@@ -42,9 +52,10 @@ impl IncrementalRenderer {
 
     /// Remove a route from the cache.
     pub fn invalidate(&mut self, route: &str) {
-        self.memory_cache.invalidate(route);
+        let route = self.cache_key(route);
+        self.memory_cache.invalidate(&route);
         #[cfg(not(target_arch = "wasm32"))]
-        self.file_system_cache.invalidate(route);
+        self.file_system_cache.invalidate(&route);
     }
 
     /// Remove all routes from the cache.
@@ -54,6 +65,42 @@ impl IncrementalRenderer {
         self.file_system_cache.clear();
     }
 
+    /// Iterate over the routes in the memory cache from the most to the least recently used. Routes that were only read from or
+    /// written to the file system cache are not included.
+    ///
+    /// ```rust
+    /// # use dioxus_isrg::IncrementalRenderer;
+    /// let mut renderer = IncrementalRenderer::builder().build();
+    /// renderer.cache("/index".to_string(), "Hello world").unwrap();
+    /// let routes: Vec<_> = renderer.memory_cache_entries().map(|entry| entry.route).collect();
+    /// assert_eq!(routes, ["/index"]);
+    /// ```
+    pub fn memory_cache_entries(&self) -> impl Iterator<Item = CacheEntry<'_>> {
+        self.memory_cache
+            .iter()
+            .map(|(route, created_at, size)| CacheEntry {
+                route,
+                created_at,
+                size,
+            })
+    }
+
+    /// Count the routes in the memory cache and the distinct renders they share. Routes that render identical responses point at
+    /// the same stored render, so `stored_bytes` is the memory the renders actually use.
+    ///
+    /// ```rust
+    /// # use dioxus_isrg::IncrementalRenderer;
+    /// let mut renderer = IncrementalRenderer::builder().build();
+    /// renderer.cache("/empty/1".to_string(), "No results").unwrap();
+    /// renderer.cache("/empty/2".to_string(), "No results").unwrap();
+    /// let stats = renderer.memory_cache_stats();
+    /// assert_eq!((stats.routes, stats.unique_renders), (2, 1));
+    /// assert_eq!(stats.stored_bytes * 2, stats.route_bytes);
+    /// ```
+    pub fn memory_cache_stats(&self) -> MemoryCacheStats {
+        self.memory_cache.stats()
+    }
+
     /// Cache a rendered response.
     ///
     /// ```rust
@@ -68,12 +115,44 @@ impl IncrementalRenderer {
         route: String,
         html: impl Into<Vec<u8>>,
     ) -> Result<RenderFreshness, IncrementalRendererError> {
+        self.cache_with_preloads(route, html, Vec::new())
+    }
+
+    /// Cache a rendered response with the values of the `Link` preload headers it should be served with. The preloads are returned
+    /// with the response from [`Self::get`], so cache hits can send the headers without reading the response.
+    ///
+    /// ```rust
+    /// # use dioxus_isrg::IncrementalRenderer;
+    /// # let mut renderer = IncrementalRenderer::builder().build();
+    /// let preloads = vec!["</assets/main.js>; rel=modulepreload".to_string()];
+    /// renderer.cache_with_preloads("/index".to_string(), "Hello world", preloads).unwrap();
+    /// let cached = renderer.get("/index").unwrap().unwrap();
+    /// assert_eq!(cached.preloads, ["</assets/main.js>; rel=modulepreload"]);
+    /// ```
+    pub fn cache_with_preloads(
+        &mut self,
+        route: String,
+        html: impl Into<Vec<u8>>,
+        preloads: Vec<String>,
+    ) -> Result<RenderFreshness, IncrementalRendererError> {
+        let route = self.cache_key(&route).into_owned();
         let timestamp = Utc::now();
         let html = html.into();
         #[cfg(not(target_arch = "wasm32"))]
-        self.file_system_cache
-            .put(route.clone(), timestamp, html.clone())?;
-        self.memory_cache.put(route, timestamp, html);
+        if let Err(error) =
+            self.file_system_cache
+                .put(route.clone(), timestamp, html.clone(), &preloads)
+        {
+            tracing::warn!("Failed to write route \"{route}\" to the cache: {error}");
+            if let Some(on_cache_error) = &self.on_cache_error {
+                on_cache_error(&CacheError {
+                    route: &route,
+                    error: &error,
+                });
+            }
+            return Err(error);
+        }
+        self.memory_cache.put(route, timestamp, html, preloads);
         Ok(RenderFreshness::created_at(
             timestamp,
             self.invalidate_after,
@@ -106,6 +185,7 @@ impl IncrementalRenderer {
         &'a mut self,
         route: &str,
     ) -> Result<Option<CachedRender<'a>>, IncrementalRendererError> {
+        let key = self.cache_key(route).into_owned();
         let Self {
             memory_cache,
             #[cfg(not(target_arch = "wasm32"))]
@@ -124,8 +204,10 @@ impl IncrementalRenderer {
         let or_insert = || {
             // check the file cache
             #[cfg(not(target_arch = "wasm32"))]
-            return match file_system_cache.get(route) {
-                Ok(Some((freshness, bytes))) => Ok((freshness.timestamp(), bytes)),
+            return match file_system_cache.get(&key) {
+                Ok(Some((freshness, bytes, preloads))) => {
+                    Ok((freshness.timestamp(), bytes, preloads))
+                }
                 Ok(None) => Err(FsGetError::NotPresent),
                 Err(e) => Err(FsGetError::Error(e)),
             };
@@ -134,16 +216,88 @@ impl IncrementalRenderer {
             Err(FsGetError::NotPresent)
         };
 
-        match memory_cache.try_get_or_insert(route, or_insert) {
-            Ok(Some((freshness, bytes))) => Ok(Some(CachedRender {
+        match memory_cache.try_get_or_insert(&key, or_insert) {
+            Ok(Some((freshness, bytes, preloads))) => Ok(Some(CachedRender {
                 route: route.to_string(),
                 freshness,
                 response: bytes,
+                preloads,
             })),
             Err(FsGetError::NotPresent) | Ok(None) => Ok(None),
             Err(FsGetError::Error(e)) => Err(e),
         }
     }
+
+    /// Get the key a route is cached under
+    fn cache_key<'r>(&self, route: &'r str) -> Cow<'r, str> {
+        if !self.normalize_query {
+            return Cow::Borrowed(route);
+        }
+        let Some((path, query)) = route.split_once('?') else {
+            return Cow::Borrowed(route);
+        };
+
+        let mut params: Vec<&str> = query.split('&').filter(|param| !param.is_empty()).collect();
+        // The sort is stable, so values of the same parameter keep their order
+        params.sort_by_key(|param| param.split_once('=').map_or(*param, |(name, _)| name));
+        let mut unique: Vec<&str> = Vec::with_capacity(params.len());
+        for param in params {
+            if !unique.contains(&param) {
+                unique.push(param);
+            }
+        }
+
+        if unique.is_empty() {
+            Cow::Borrowed(path)
+        } else {
+            Cow::Owned(format!("{path}?{}", unique.join("&")))
+        }
+    }
+}
+
+/// A route in the memory cache. See [`IncrementalRenderer::memory_cache_entries`]
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct CacheEntry<'a> {
+    /// The route that was rendered
+    pub route: &'a str,
+    /// When the route was rendered
+    pub created_at: DateTime<Utc>,
+    /// The size of the rendered response in bytes
+    pub size: usize,
+}
+
+/// The number of routes in the memory cache and the distinct renders they share. See [`IncrementalRenderer::memory_cache_stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MemoryCacheStats {
+    /// The number of routes in the cache
+    pub routes: usize,
+    /// The number of distinct renders the routes point at
+    pub unique_renders: usize,
+    /// The size of the render of every route added together, counting shared renders once per route
+    pub route_bytes: usize,
+    /// The size of the distinct renders that are stored
+    pub stored_bytes: usize,
+}
+
+/// A render that could not be written to the cache. See [`IncrementalRendererConfig::on_cache_error`]
+#[derive(Debug)]
+pub struct CacheError<'a> {
+    route: &'a str,
+    error: &'a IncrementalRendererError,
+}
+
+impl CacheError<'_> {
+    /// Get the route that could not be cached
+    pub fn route(&self) -> &str {
+        self.route
+    }
+
+    /// Get the error that occurred while writing the route
+    pub fn error(&self) -> &IncrementalRendererError {
+        self.error
+    }
 }
 
 /// An error that can occur while rendering a route or retrieving a cached route.
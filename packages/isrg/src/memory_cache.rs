@@ -4,22 +4,64 @@
 
 use chrono::offset::Utc;
 use chrono::DateTime;
-use rustc_hash::FxHasher;
-use std::{hash::BuildHasherDefault, num::NonZeroUsize};
+use rustc_hash::{FxHashMap, FxHasher};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Weak};
 
 use super::freshness::RenderFreshness;
+use super::MemoryCacheStats;
 
 pub(crate) struct InMemoryCache {
     #[allow(clippy::type_complexity)]
-    lru: Option<lru::LruCache<String, (DateTime<Utc>, Vec<u8>), BuildHasherDefault<FxHasher>>>,
+    lru: Option<lru::LruCache<String, CachedEntry, BuildHasherDefault<FxHasher>>>,
+    /// Renders are stored by the hash of their content so routes with identical renders share one blob. Blobs are freed when the last
+    /// route that points at them is evicted, which leaves a dead entry here until the next put prunes it.
+    blobs: Blobs,
     invalidate_after: Option<std::time::Duration>,
 }
 
+/// When a route was rendered, the shared render and the preload links of the render
+type CachedEntry = (DateTime<Utc>, Arc<[u8]>, Arc<[String]>);
+
+/// The cached renders keyed by the hash of their content. Hash collisions keep every distinct render in the same bucket.
+#[derive(Default)]
+struct Blobs(FxHashMap<u64, Vec<Weak<[u8]>>>);
+
+impl Blobs {
+    /// Get the shared blob for some data, or store the data as a new blob
+    fn intern(&mut self, data: Vec<u8>) -> Arc<[u8]> {
+        let mut hasher = FxHasher::default();
+        data.hash(&mut hasher);
+        let bucket = self.0.entry(hasher.finish()).or_default();
+        bucket.retain(|blob| blob.strong_count() > 0);
+        if let Some(blob) = bucket
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|blob| **blob == *data)
+        {
+            return blob;
+        }
+        let blob: Arc<[u8]> = data.into();
+        bucket.push(Arc::downgrade(&blob));
+        blob
+    }
+
+    /// Remove the blobs that no route points at anymore
+    fn prune(&mut self) {
+        self.0.retain(|_, bucket| {
+            bucket.retain(|blob| blob.strong_count() > 0);
+            !bucket.is_empty()
+        });
+    }
+}
+
 impl InMemoryCache {
     pub fn new(memory_cache_limit: usize, invalidate_after: Option<std::time::Duration>) -> Self {
         Self {
             lru: NonZeroUsize::new(memory_cache_limit)
                 .map(|limit| lru::LruCache::with_hasher(limit, Default::default())),
+            blobs: Blobs::default(),
             invalidate_after,
         }
     }
@@ -28,11 +70,47 @@ impl InMemoryCache {
         if let Some(cache) = &mut self.lru {
             cache.clear();
         }
+        self.blobs.prune();
     }
 
-    pub fn put(&mut self, route: String, timestamp: DateTime<Utc>, data: Vec<u8>) {
+    /// Count the routes and the distinct renders they point at
+    pub fn stats(&self) -> MemoryCacheStats {
+        let mut blobs = rustc_hash::FxHashSet::default();
+        let mut stats = MemoryCacheStats::default();
+        for (_, (_, data, _)) in self.lru.iter().flat_map(|cache| cache.iter()) {
+            stats.routes += 1;
+            stats.route_bytes += data.len();
+            if blobs.insert(Arc::as_ptr(data) as *const u8) {
+                stats.unique_renders += 1;
+                stats.stored_bytes += data.len();
+            }
+        }
+        stats
+    }
+
+    /// Iterate over the cached routes with their timestamps and sizes from the most to the least recently used
+    pub fn iter(&self) -> impl Iterator<Item = (&str, DateTime<Utc>, usize)> {
+        self.lru.iter().flat_map(|cache| {
+            cache
+                .iter()
+                .map(|(route, (timestamp, data, _))| (route.as_str(), *timestamp, data.len()))
+        })
+    }
+
+    pub fn put(
+        &mut self,
+        route: String,
+        timestamp: DateTime<Utc>,
+        data: Vec<u8>,
+        preloads: Vec<String>,
+    ) {
         if let Some(cache) = &mut self.lru {
-            cache.put(route, (timestamp, data));
+            let data = self.blobs.intern(data);
+            cache.put(route, (timestamp, data, preloads.into()));
+            // Evicted routes leave dead blobs behind. Prune them once they outnumber the routes
+            if self.blobs.0.len() > cache.cap().get() * 2 {
+                self.blobs.prune();
+            }
         }
     }
 
@@ -42,13 +120,23 @@ impl InMemoryCache {
         }
     }
 
-    pub fn try_get_or_insert<'a, F: FnOnce() -> Result<(DateTime<Utc>, Vec<u8>), E>, E>(
+    #[allow(clippy::type_complexity)]
+    pub fn try_get_or_insert<
+        'a,
+        F: FnOnce() -> Result<(DateTime<Utc>, Vec<u8>, Vec<String>), E>,
+        E,
+    >(
         &'a mut self,
         route: &str,
         or_insert: F,
-    ) -> Result<Option<(RenderFreshness, &'a [u8])>, E> {
+    ) -> Result<Option<(RenderFreshness, &'a [u8], &'a [String])>, E> {
         if let Some(memory_cache) = self.lru.as_mut() {
-            let (timestamp, _) = memory_cache.try_get_or_insert(route.to_string(), or_insert)?;
+            let blobs = &mut self.blobs;
+            let (timestamp, _, _) = memory_cache.try_get_or_insert(route.to_string(), || {
+                or_insert().map(|(timestamp, data, preloads)| {
+                    (timestamp, blobs.intern(data), preloads.into())
+                })
+            })?;
 
             let now = Utc::now();
             let elapsed = timestamp.signed_duration_since(now);
@@ -64,19 +152,19 @@ impl InMemoryCache {
 
             // We need to reborrow because we may have invalidated the lifetime if the route was removed.
             // We know it wasn't because we returned... but rust doesn't understand that.
-            let (timestamp, cache_hit) = memory_cache.get(route).unwrap();
+            let (timestamp, cache_hit, preloads) = memory_cache.get(route).unwrap();
 
             return match self.invalidate_after {
                 Some(invalidate_after) => {
                     tracing::trace!("memory cache hit");
                     let max_age = invalidate_after.as_secs();
                     let freshness = RenderFreshness::new(age as u64, max_age, *timestamp);
-                    Ok(Some((freshness, cache_hit)))
+                    Ok(Some((freshness, &**cache_hit, &**preloads)))
                 }
                 None => {
                     tracing::trace!("memory cache hit");
                     let freshness = RenderFreshness::new_age(age as u64, *timestamp);
-                    Ok(Some((freshness, cache_hit)))
+                    Ok(Some((freshness, &**cache_hit, &**preloads)))
                 }
             };
         }
@@ -731,11 +731,13 @@ impl RouteEnum {
         let site_map = &self.site_map;
 
         let mut matches = Vec::new();
+        let mut pattern_matches = Vec::new();
 
         // Collect all routes matches
         for route in &self.endpoints {
             if let RouteEndpoint::Route(route) = route {
                 matches.push(route.routable_match(&self.layouts, &self.nests));
+                pattern_matches.push(route.pattern_match(&self.nests));
             }
         }
 
@@ -752,6 +754,12 @@ impl RouteEnum {
                         _ => VNode::empty()
                     }
                 }
+
+                fn pattern(&self) -> String {
+                    match self {
+                        #(#pattern_matches)*
+                    }
+                }
             }
         }
     }
@@ -211,6 +211,46 @@ impl Route {
         }
     }
 
+    pub fn pattern_match(&self, nests: &[Nest]) -> TokenStream2 {
+        let name = &self.route_name;
+        let pattern: String = self
+            .nests
+            .iter()
+            .flat_map(|id| nests[id.0].segments.iter())
+            .chain(self.segments.iter())
+            .filter_map(|segment| segment.pattern_segment())
+            .collect();
+
+        match &self.ty {
+            RouteType::Child(field) => {
+                let child = field.ident.as_ref().unwrap();
+                quote! {
+                    Self::#name { #child, .. } => {
+                        let mut pattern = String::from(#pattern);
+                        let child = dioxus_router::routable::Routable::pattern(#child);
+                        if child != "/" {
+                            pattern.push_str(&child);
+                        }
+                        if pattern.is_empty() {
+                            pattern.push('/');
+                        }
+                        pattern
+                    }
+                }
+            }
+            RouteType::Leaf { .. } => {
+                let pattern = if pattern.is_empty() {
+                    "/".to_string()
+                } else {
+                    pattern
+                };
+                quote! {
+                    Self::#name { .. } => #pattern.to_string(),
+                }
+            }
+        }
+    }
+
     pub fn routable_match(&self, layouts: &[Layout], nests: &[Nest]) -> TokenStream2 {
         let name = &self.route_name;
 
@@ -21,6 +21,16 @@ impl RouteSegment {
         }
     }
 
+    /// The segment as it appears in the `#[route]` attribute. Empty static segments are skipped.
+    pub fn pattern_segment(&self) -> Option<String> {
+        match self {
+            Self::Static(segment) if segment.is_empty() => None,
+            Self::Static(segment) => Some(format!("/{}", segment)),
+            Self::Dynamic(ident, _) => Some(format!("/:{}", ident)),
+            Self::CatchAll(ident, _) => Some(format!("/:..{}", ident)),
+        }
+    }
+
     pub fn write_segment(&self) -> TokenStream2 {
         match self {
             Self::Static(segment) => quote! { write!(f, "/{}", #segment)?; },
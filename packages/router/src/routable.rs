@@ -599,6 +599,9 @@ pub trait Routable: FromStr + Display + Clone + 'static {
     /// Render the route at the given level
     fn render(&self, level: usize) -> Element;
 
+    /// The pattern of the route this value was parsed from, including any `#[nest]` prefixes. The pattern uses the same syntax as the `#[route]` attribute without the query and hash.
+    fn pattern(&self) -> String;
+
     /// Checks if this route is a child of the given route.
     ///
     /// # Example
@@ -707,6 +710,58 @@ pub trait Routable: FromStr + Display + Clone + 'static {
             })
             .collect()
     }
+
+    /// Find the route pattern that matches a concrete path. The pattern uses the same syntax as the `#[route]` attribute.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dioxus_router::prelude::*;
+    /// use dioxus::prelude::*;
+    ///
+    /// #[component]
+    /// fn Home() -> Element { VNode::empty() }
+    /// #[component]
+    /// fn Product(id: usize) -> Element { VNode::empty() }
+    ///
+    /// #[derive(Routable, Clone, PartialEq, Debug)]
+    /// enum Route {
+    ///     #[route("/")]
+    ///     Home {},
+    ///     #[route("/products/:id")]
+    ///     Product { id: usize },
+    /// }
+    ///
+    /// assert_eq!(Route::route_pattern("/products/42?ref=home"), Some("/products/:id".to_string()));
+    /// assert_eq!(Route::route_pattern("/"), Some("/".to_string()));
+    /// assert_eq!(Route::route_pattern("/missing/page"), None);
+    /// // `abc` is not a valid id, so the path doesn't match the product route
+    /// assert_eq!(Route::route_pattern("/products/abc"), None);
+    /// ```
+    ///
+    /// The pattern comes from the route the path parses into, so static routes win over dynamic routes that are declared first:
+    /// ```rust
+    /// use dioxus_router::prelude::*;
+    /// use dioxus::prelude::*;
+    ///
+    /// #[component]
+    /// fn Post(id: usize) -> Element { VNode::empty() }
+    /// #[component]
+    /// fn About() -> Element { VNode::empty() }
+    ///
+    /// #[derive(Routable, Clone, PartialEq, Debug)]
+    /// enum Route {
+    ///     #[route("/:id")]
+    ///     Post { id: usize },
+    ///     #[route("/about")]
+    ///     About {},
+    /// }
+    ///
+    /// assert_eq!(Route::route_pattern("/about"), Some("/about".to_string()));
+    /// assert_eq!(Route::route_pattern("/42"), Some("/:id".to_string()));
+    /// ```
+    fn route_pattern(path: &str) -> Option<String> {
+        Self::from_str(path).ok().map(|route| route.pattern())
+    }
 }
 
 /// A type erased map of the site structure.
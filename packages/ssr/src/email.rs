@@ -0,0 +1,343 @@
+//! Render components into html for emails.
+//!
+//! Most email clients strip `<style>` elements and never run scripts. An [`EmailRenderer`] renders a component without any
+//! hydration data, removes scripts and inlines the css from `<style>` elements and extra stylesheets into the `style`
+//! attribute of each element.
+
+use crate::tags::{escape_attribute, Tag, Token, Tokens};
+use crate::Renderer;
+use dioxus_core::{Element, VirtualDom};
+
+/// A renderer that creates email safe html documents from components.
+///
+/// Rules with simple selectors (`*`, `p`, `.class`, `#id` and compounds like `p.note`) are inlined. More complex rules that use
+/// combinators, pseudo classes, attribute selectors or at-rules like `@media` can't be inlined. They are kept in a `<style>` element
+/// in the head for the email clients that support it.
+///
+/// # Example
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_ssr::email::EmailRenderer;
+///
+/// let mut renderer = EmailRenderer::new().with_stylesheet("p { color: red; } .note { font-size: 12px; }");
+/// let html = renderer.render_element(rsx! {
+///     p { class: "note", "Your order has shipped" }
+/// });
+/// assert!(html.contains(r#"<p class="note" style="color:red;font-size:12px;">Your order has shipped</p>"#));
+/// ```
+#[derive(Default)]
+pub struct EmailRenderer {
+    renderer: Renderer,
+    stylesheets: Vec<String>,
+    title: Option<String>,
+}
+
+impl EmailRenderer {
+    /// Create a new email renderer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add css that is inlined into every email this renderer creates
+    pub fn with_stylesheet(mut self, css: impl Into<String>) -> Self {
+        self.stylesheets.push(css.into());
+        self
+    }
+
+    /// Set the title of the email document
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Render a virtual dom into an email document
+    pub fn render(&mut self, dom: &VirtualDom) -> String {
+        let pre_render = std::mem::replace(&mut self.renderer.pre_render, false);
+        let body = self.renderer.render(dom);
+        self.renderer.pre_render = pre_render;
+        self.document(&body)
+    }
+
+    /// Render an element into an email document
+    pub fn render_element(&mut self, element: Element) -> String {
+        let pre_render = std::mem::replace(&mut self.renderer.pre_render, false);
+        let body = self.renderer.render_element(element);
+        self.renderer.pre_render = pre_render;
+        self.document(&body)
+    }
+
+    /// Wrap some rendered html in a minimal email document with all styles inlined
+    fn document(&self, body: &str) -> String {
+        let (body, inline_styles) = strip_scripts_and_styles(body);
+
+        let mut rules = Vec::new();
+        let mut head_styles = String::new();
+        for css in self
+            .stylesheets
+            .iter()
+            .map(String::as_str)
+            .chain(inline_styles.iter().map(String::as_str))
+        {
+            parse_stylesheet(css, &mut rules, &mut head_styles);
+        }
+        let body = inline_rules(&body, &rules);
+
+        let mut document = String::from(
+            r#"<!DOCTYPE html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1">"#,
+        );
+        if let Some(title) = &self.title {
+            document.push_str("<title>");
+            document.push_str(&askama_escape::escape(title, askama_escape::Html).to_string());
+            document.push_str("</title>");
+        }
+        if !head_styles.is_empty() {
+            document.push_str("<style>");
+            document.push_str(&head_styles);
+            document.push_str("</style>");
+        }
+        document.push_str("</head><body>");
+        document.push_str(&body);
+        document.push_str("</body></html>");
+        document
+    }
+}
+
+/// A css rule with a simple selector that can be inlined
+struct Rule {
+    selector: SimpleSelector,
+    /// The specificity of the selector as (ids, classes, tags)
+    specificity: (usize, usize, usize),
+    /// The position of the rule in the stylesheets. Later rules win if the specificity is the same
+    order: usize,
+    declarations: Vec<(String, String)>,
+}
+
+#[derive(Default)]
+struct SimpleSelector {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl SimpleSelector {
+    /// Parse a compound selector like `p.note#intro`. Returns `None` if the selector can't be inlined
+    fn parse(selector: &str) -> Option<Self> {
+        let selector = selector.trim();
+        if selector.is_empty()
+            || selector.contains(|c: char| {
+                c.is_whitespace() || matches!(c, '>' | '+' | '~' | ':' | '[' | '(')
+            })
+        {
+            return None;
+        }
+
+        let mut parsed = Self::default();
+        let mut rest = selector;
+        // The tag comes first if there is one
+        let tag_end = rest.find(['.', '#']).unwrap_or(rest.len());
+        match &rest[..tag_end] {
+            "" | "*" => {}
+            tag => parsed.tag = Some(tag.to_ascii_lowercase()),
+        }
+        rest = &rest[tag_end..];
+
+        while !rest.is_empty() {
+            let kind = rest.as_bytes()[0];
+            let name_end = rest[1..].find(['.', '#']).map_or(rest.len(), |end| end + 1);
+            let name = &rest[1..name_end];
+            if name.is_empty() {
+                return None;
+            }
+            match kind {
+                b'.' => parsed.classes.push(name.to_string()),
+                _ => parsed.id = Some(name.to_string()),
+            }
+            rest = &rest[name_end..];
+        }
+
+        Some(parsed)
+    }
+
+    fn specificity(&self) -> (usize, usize, usize) {
+        (
+            self.id.is_some() as usize,
+            self.classes.len(),
+            self.tag.is_some() as usize,
+        )
+    }
+
+    fn matches(&self, element: &Tag) -> bool {
+        self.tag.as_deref().map_or(true, |tag| element.is(tag))
+            && self
+                .id
+                .as_deref()
+                .map_or(true, |id| element.attribute("id") == Some(id))
+            && self.classes.iter().all(|class| {
+                element
+                    .attribute("class")
+                    .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+            })
+    }
+}
+
+/// Parse the rules of a stylesheet. Rules that can be inlined are added to `rules`, everything else is added to `head_styles`
+fn parse_stylesheet(css: &str, rules: &mut Vec<Rule>, head_styles: &mut String) {
+    let css = strip_comments(css);
+    let mut rest = css.as_str();
+    while let Some(open) = rest.find('{') {
+        let prelude = rest[..open].trim();
+        // Find the end of the block, including any nested blocks in at-rules
+        let mut depth = 0;
+        let mut close = rest.len();
+        for (index, c) in rest[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = open + index;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let block = &rest[open + 1..close];
+        let rule_end = (close + 1).min(rest.len());
+
+        let selectors: Option<Vec<_>> = if prelude.starts_with('@') {
+            None
+        } else {
+            prelude.split(',').map(SimpleSelector::parse).collect()
+        };
+        match selectors {
+            Some(selectors) => {
+                let declarations = parse_declarations(block);
+                for selector in selectors {
+                    rules.push(Rule {
+                        specificity: selector.specificity(),
+                        selector,
+                        order: rules.len(),
+                        declarations: declarations.clone(),
+                    });
+                }
+            }
+            None => head_styles.push_str(rest[..rule_end].trim()),
+        }
+
+        rest = &rest[rule_end..];
+    }
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// Parse `property: value` pairs separated by `;`
+fn parse_declarations(block: &str) -> Vec<(String, String)> {
+    block
+        .split(';')
+        .filter_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let (property, value) = (property.trim(), value.trim());
+            (!property.is_empty() && !value.is_empty())
+                .then(|| (property.to_ascii_lowercase(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Set a declaration, replacing any earlier value for the same property
+fn set_declaration(declarations: &mut Vec<(String, String)>, property: String, value: String) {
+    declarations.retain(|(existing, _)| *existing != property);
+    declarations.push((property, value));
+}
+
+/// Remove all script and style elements from some html and return the contents of the style elements
+fn strip_scripts_and_styles(html: &str) -> (String, Vec<String>) {
+    let mut stripped = String::with_capacity(html.len());
+    let mut styles = Vec::new();
+    // The element whose contents are being removed
+    let mut removing: Option<&str> = None;
+    for token in Tokens::new(html) {
+        match token {
+            Token::Tag(tag) if tag.is("script") || tag.is("style") => {
+                removing = (!tag.is_closing() && !tag.is_self_closing()).then(|| tag.name());
+            }
+            Token::Text(text) if removing.is_some() => {
+                if removing.is_some_and(|name| name.eq_ignore_ascii_case("style")) {
+                    styles.push(text.to_string());
+                }
+            }
+            token => stripped.push_str(token.source()),
+        }
+    }
+    (stripped, styles)
+}
+
+/// Inline the declarations of every matching rule into the style attribute of each element
+fn inline_rules(html: &str, rules: &[Rule]) -> String {
+    let mut sorted: Vec<&Rule> = rules.iter().collect();
+    sorted.sort_by_key(|rule| (rule.specificity, rule.order));
+
+    let mut inlined = String::with_capacity(html.len());
+    for token in Tokens::new(html) {
+        let element = match token {
+            Token::Tag(tag) if !tag.is_closing() => tag,
+            token => {
+                inlined.push_str(token.source());
+                continue;
+            }
+        };
+        let mut declarations = Vec::new();
+        for rule in sorted.iter().filter(|rule| rule.selector.matches(&element)) {
+            for (property, value) in &rule.declarations {
+                set_declaration(&mut declarations, property.clone(), escape_attribute(value));
+            }
+        }
+        if declarations.is_empty() {
+            inlined.push_str(element.source());
+            continue;
+        }
+        // Styles that are already inline are more specific than any stylesheet
+        if let Some(style) = element.attribute("style") {
+            for (property, value) in parse_declarations(style) {
+                set_declaration(&mut declarations, property, value);
+            }
+        }
+
+        inlined.push('<');
+        inlined.push_str(element.name());
+        for (key, value) in element
+            .attributes()
+            .filter(|(key, _)| !key.eq_ignore_ascii_case("style"))
+        {
+            inlined.push(' ');
+            inlined.push_str(key);
+            inlined.push_str("=\"");
+            inlined.push_str(value);
+            inlined.push('"');
+        }
+        inlined.push_str(" style=\"");
+        for (property, value) in declarations {
+            inlined.push_str(&property);
+            inlined.push(':');
+            inlined.push_str(&value);
+            inlined.push(';');
+        }
+        inlined.push('"');
+        if element.is_self_closing() {
+            inlined.push('/');
+        }
+        inlined.push('>');
+    }
+    inlined
+}
@@ -4,12 +4,14 @@
 
 mod cache;
 pub mod config;
+pub mod email;
 pub mod renderer;
+pub mod tags;
 pub mod template;
 
 use dioxus_core::{Element, VirtualDom};
 
-pub use crate::renderer::Renderer;
+pub use crate::renderer::{BoundaryInfo, Renderer};
 
 /// A convenience function to render an `rsx!` call to a string
 ///
@@ -24,6 +24,19 @@ pub struct Renderer {
 
     /// The current dynamic node id for hydration
     dynamic_node_id: usize,
+
+    /// The suspense boundaries seen during the current render if they are being collected
+    boundaries: Option<Vec<BoundaryInfo>>,
+}
+
+/// A suspense boundary that was rendered. See [`Renderer::render_with_boundaries`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BoundaryInfo {
+    /// The scope of the suspense boundary
+    pub id: ScopeId,
+    /// If the children of the boundary were rendered. This is false if the boundary had suspended tasks and rendered its fallback
+    pub resolved: bool,
 }
 
 impl Renderer {
@@ -62,6 +75,17 @@ impl Renderer {
         self.render_scope(buf, dom, ScopeId::ROOT)
     }
 
+    /// Render the VirtualDom to a string and collect every suspense boundary that was rendered in document order.
+    ///
+    /// This includes the suspense boundary the VirtualDom wraps around the root component, and boundaries that are rendered by the
+    /// [`Self::set_render_components`] callback. Boundaries inside a fallback are not rendered, so they are not included.
+    pub fn render_with_boundaries(&mut self, dom: &VirtualDom) -> (String, Vec<BoundaryInfo>) {
+        self.boundaries = Some(Vec::new());
+        let html = self.render(dom);
+        let boundaries = self.boundaries.take().unwrap_or_default();
+        (html, boundaries)
+    }
+
     /// Render an element to a string
     pub fn render_element(&mut self, element: Element) -> String {
         let mut buf = String::new();
@@ -160,6 +184,20 @@ impl Renderer {
                 }
                 Segment::Node(idx) => match &template.dynamic_nodes[*idx] {
                     DynamicNode::Component(node) => {
+                        if let Some(boundaries) = &mut self.boundaries {
+                            let scope_id = node.mounted_scope_id(*idx, template, dom).unwrap();
+                            if let Some(suspense) =
+                                SuspenseContext::downcast_suspense_boundary_from_scope(
+                                    &dom.runtime(),
+                                    scope_id,
+                                )
+                            {
+                                boundaries.push(BoundaryInfo {
+                                    id: scope_id,
+                                    resolved: !suspense.has_suspended_tasks(),
+                                });
+                            }
+                        }
                         if let Some(render_components) = self.render_components.clone() {
                             let scope_id = node.mounted_scope_id(*idx, template, dom).unwrap();
 
@@ -0,0 +1,278 @@
+//! Split html into text, comments and tags.
+//!
+//! This is a small tokenizer for html that was rendered by dioxus or written by hand in templates like the `index.html`. It is
+//! meant for post processing the rendered html, not for parsing arbitrary documents. The tokens cover the html without gaps,
+//! so writing the [`Token::source`] of every token gives back the original html.
+//!
+//! # Example
+//! ```rust
+//! use dioxus_ssr::tags::{Token, Tokens};
+//!
+//! let html = r#"<p class="note">Hi<!-- comment --></p><script>if (a < b) {}</script>"#;
+//! let names: Vec<_> = Tokens::new(html)
+//!     .filter_map(|token| match token {
+//!         Token::Tag(tag) if !tag.is_closing() => Some(tag.name()),
+//!         _ => None,
+//!     })
+//!     .collect();
+//! assert_eq!(names, ["p", "script"]);
+//! assert_eq!(Tokens::new(html).map(|token| token.source()).collect::<String>(), html);
+//! ```
+
+/// Elements whose contents are text even if they look like tags
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// A piece of html returned by [`Tokens`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// Text between tags. The contents of raw text elements like `<script>` are always text
+    Text(&'a str),
+    /// A comment, doctype or other markup declaration like `<!-- -->` or `<!DOCTYPE html>`
+    Comment(&'a str),
+    /// An opening or closing tag
+    Tag(Tag<'a>),
+}
+
+impl<'a> Token<'a> {
+    /// Get the html of the token
+    pub fn source(&self) -> &'a str {
+        match self {
+            Self::Text(source) | Self::Comment(source) => source,
+            Self::Tag(tag) => tag.source,
+        }
+    }
+}
+
+/// An opening or closing tag like `<div class="note">` or `</div>`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tag<'a> {
+    source: &'a str,
+    name: &'a str,
+    attributes: &'a str,
+    closing: bool,
+}
+
+impl<'a> Tag<'a> {
+    /// Parse the tag at the start of some html. Returns `None` if the html doesn't start with a complete tag
+    fn parse(html: &'a str) -> Option<Self> {
+        let after_bracket = html.strip_prefix('<')?;
+        let (closing, inner) = match after_bracket.strip_prefix('/') {
+            Some(inner) => (true, inner),
+            None => (false, after_bracket),
+        };
+        if !inner.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let name_end = inner
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .unwrap_or(inner.len());
+        let (name, mut rest) = inner.split_at(name_end);
+        while next_attribute(&mut rest).is_some() {}
+
+        let end = if rest.starts_with('>') {
+            1
+        } else if rest.starts_with("/>") {
+            2
+        } else {
+            return None;
+        };
+        let attributes_start = html.len() - inner.len() + name_end;
+        let attributes_end = html.len() - rest.len();
+        Some(Self {
+            source: &html[..attributes_end + end],
+            name,
+            attributes: &html[attributes_start..attributes_end],
+            closing,
+        })
+    }
+
+    /// Get the html of the whole tag
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Get the name of the tag as it is written in the html
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Check if this is a tag for the element `name`. Tag names are compared case-insensitively
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    /// Check if this is a closing tag like `</div>`
+    pub fn is_closing(&self) -> bool {
+        self.closing
+    }
+
+    /// Check if this is a self closing tag like `<img />`
+    pub fn is_self_closing(&self) -> bool {
+        self.source.ends_with("/>")
+    }
+
+    /// Get the names and values of the attributes of the tag. Values are returned as they are written in the html without the
+    /// quotes. Attributes without a value have an empty value
+    pub fn attributes(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        let mut rest = self.attributes;
+        std::iter::from_fn(move || next_attribute(&mut rest))
+    }
+
+    /// Get the value of an attribute. Attribute names are compared case-insensitively
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes()
+            .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Get the html of the tag with some extra attributes added after the existing attributes
+    pub fn with_attributes(&self, attributes: &str) -> String {
+        let (before, after) = self
+            .source
+            .split_at(self.source.len() - self.source.ends_with("/>") as usize - 1);
+        format!("{before}{attributes}{after}")
+    }
+}
+
+/// Split the next attribute off of the inside of a tag. Returns `None` once the rest starts with the end of the tag
+fn next_attribute<'a>(rest: &mut &'a str) -> Option<(&'a str, &'a str)> {
+    loop {
+        *rest = rest.trim_start();
+        // A `/` that doesn't close the tag is ignored like in browsers
+        match rest.strip_prefix('/') {
+            Some(after) if !after.starts_with('>') => *rest = after,
+            _ => break,
+        }
+    }
+    if rest.is_empty() || rest.starts_with('>') || rest.starts_with("/>") {
+        return None;
+    }
+    // The first character is part of the name even if it is a `=`
+    let first_len = rest.chars().next().map_or(0, char::len_utf8);
+    let name_end = rest[first_len..]
+        .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/' || c == '>')
+        .map_or(rest.len(), |end| end + first_len);
+    let name = &rest[..name_end];
+    *rest = &rest[name_end..];
+
+    let Some(value) = rest.trim_start().strip_prefix('=') else {
+        return Some((name, ""));
+    };
+    let value = value.trim_start();
+    let (value, remaining) = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => match value[1..].find(quote) {
+            Some(end) => (&value[1..end + 1], &value[end + 2..]),
+            None => (&value[1..], ""),
+        },
+        _ => {
+            let end = value
+                .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                .unwrap_or(value.len());
+            value.split_at(end)
+        }
+    };
+    *rest = remaining;
+    Some((name, value))
+}
+
+/// An iterator over the [`Token`]s of some html
+#[derive(Clone, Debug)]
+pub struct Tokens<'a> {
+    rest: &'a str,
+    /// The name of the raw text element whose contents come next
+    raw_text: Option<&'a str>,
+}
+
+impl<'a> Tokens<'a> {
+    /// Split some html into tokens
+    pub fn new(html: &'a str) -> Self {
+        Self {
+            rest: html,
+            raw_text: None,
+        }
+    }
+
+    fn split_off(&mut self, len: usize) -> &'a str {
+        let (token, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        token
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        if let Some(name) = self.raw_text.take() {
+            let end = self
+                .rest
+                .match_indices("</")
+                .map(|(start, _)| start)
+                .find(|start| {
+                    self.rest[start + 2..]
+                        .get(..name.len())
+                        .is_some_and(|closing| closing.eq_ignore_ascii_case(name))
+                })
+                .unwrap_or(self.rest.len());
+            if end > 0 {
+                return Some(Token::Text(self.split_off(end)));
+            }
+        }
+
+        if self.rest.starts_with("<!--") {
+            let end = self.rest[4..]
+                .find("-->")
+                .map_or(self.rest.len(), |end| end + 7);
+            return Some(Token::Comment(self.split_off(end)));
+        }
+        if self.rest.starts_with("<!") || self.rest.starts_with("<?") {
+            let end = self.rest.find('>').map_or(self.rest.len(), |end| end + 1);
+            return Some(Token::Comment(self.split_off(end)));
+        }
+        if starts_with_markup(self.rest) {
+            let Some(tag) = Tag::parse(self.rest) else {
+                // An unfinished tag can't be split any further
+                return Some(Token::Text(self.split_off(self.rest.len())));
+            };
+            self.split_off(tag.source.len());
+            if !tag.closing
+                && !tag.is_self_closing()
+                && RAW_TEXT_ELEMENTS.iter().any(|name| tag.is(name))
+            {
+                self.raw_text = Some(tag.name);
+            }
+            return Some(Token::Tag(tag));
+        }
+
+        // Text runs until the next `<` that starts a tag or comment
+        let end = self
+            .rest
+            .match_indices('<')
+            .map(|(start, _)| start)
+            .find(|&start| starts_with_markup(&self.rest[start..]))
+            .unwrap_or(self.rest.len());
+        Some(Token::Text(self.split_off(end)))
+    }
+}
+
+/// Check if some html starts with a tag, comment or markup declaration. Other `<` characters are text
+fn starts_with_markup(html: &str) -> bool {
+    let Some(after) = html.strip_prefix('<') else {
+        return false;
+    };
+    let after = after.strip_prefix('/').unwrap_or(after);
+    after.starts_with(|c: char| c.is_ascii_alphabetic())
+        || (!html.starts_with("</") && after.starts_with(['!', '?']))
+}
+
+/// Escape a value so it can be written inside of a double quoted attribute
+pub fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
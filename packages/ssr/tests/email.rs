@@ -0,0 +1,31 @@
+use dioxus::prelude::*;
+use dioxus_ssr::email::EmailRenderer;
+
+#[test]
+fn inlines_style_elements() {
+    let html = EmailRenderer::new().render_element(rsx! {
+        style { "p {{ color: red; }} #title.big {{ font-size: 24px; }} a:hover {{ color: blue; }}" }
+        h1 { id: "title", class: "big", "Welcome" }
+        p { margin: "0", "Thanks for signing up" }
+    });
+
+    assert!(html.contains(r#"<h1 id="title" class="big" style="font-size:24px;">Welcome</h1>"#));
+    assert!(html.contains(r#"<p style="color:red;margin:0;">Thanks for signing up</p>"#));
+    // Rules that can't be inlined are kept in the head
+    assert!(html.contains("<style>a:hover { color: blue; }</style></head>"));
+}
+
+#[test]
+fn strips_scripts() {
+    let html = EmailRenderer::new()
+        .with_title("Receipt")
+        .render_element(rsx! {
+            div { "Order #1" }
+            script { "console.log('tracking')" }
+        });
+
+    assert_eq!(
+        html,
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>Receipt</title></head><body><div>Order #1</div></body></html>"#
+    );
+}
@@ -0,0 +1,36 @@
+use dioxus::prelude::*;
+
+#[test]
+fn collects_suspense_boundaries() {
+    fn app() -> Element {
+        rsx! {
+            SuspenseBoundary {
+                fallback: |_| rsx! { "loading" },
+                Pending {}
+            }
+            SuspenseBoundary {
+                fallback: |_| rsx! { "loading" },
+                div { "ready" }
+            }
+        }
+    }
+
+    #[component]
+    fn Pending() -> Element {
+        use_resource(std::future::pending::<()>).suspend()?;
+        rsx! { "never" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+
+    let (html, boundaries) = dioxus_ssr::Renderer::new().render_with_boundaries(&dom);
+    assert_eq!(html, "loading<div>ready</div>");
+
+    // The first boundary wraps the root component
+    let resolved: Vec<_> = boundaries
+        .iter()
+        .map(|boundary| boundary.resolved)
+        .collect();
+    assert_eq!(resolved, [true, false, true]);
+}
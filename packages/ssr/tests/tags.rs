@@ -0,0 +1,38 @@
+use dioxus_ssr::tags::{Token, Tokens};
+
+#[test]
+fn tokens_cover_the_html() {
+    let html =
+        r#"<!DOCTYPE html><p class='note' hidden>3 < 4<br/></p><!-- <div> --><div unfinished"#;
+    let tokens: Vec<_> = Tokens::new(html).collect();
+
+    assert_eq!(tokens.iter().map(Token::source).collect::<String>(), html);
+    assert_eq!(tokens[0], Token::Comment("<!DOCTYPE html>"));
+    assert_eq!(tokens[2], Token::Text("3 < 4"));
+    assert_eq!(tokens[5], Token::Comment("<!-- <div> -->"));
+    assert_eq!(tokens[6], Token::Text("<div unfinished"));
+}
+
+#[test]
+fn reads_attributes_with_brackets() {
+    let Some(Token::Tag(tag)) = Tokens::new(r#"<IMG alt="a > b" src=a.png>"#).next() else {
+        panic!("expected a tag");
+    };
+
+    assert!(tag.is("img"));
+    assert_eq!(tag.attribute("ALT"), Some("a > b"));
+    assert_eq!(tag.attribute("src"), Some("a.png"));
+    assert_eq!(
+        tag.with_attributes(r#" loading="lazy""#),
+        r#"<IMG alt="a > b" src=a.png loading="lazy">"#
+    );
+}
+
+#[test]
+fn raw_text_elements_contain_text() {
+    let tokens: Vec<_> = Tokens::new("<script>if (a<b) { x = '</div>' }</SCRIPT><p>").collect();
+
+    assert_eq!(tokens[1], Token::Text("if (a<b) { x = '</div>' }"));
+    assert!(matches!(tokens[2], Token::Tag(tag) if tag.is("script") && tag.is_closing()));
+    assert!(matches!(tokens[3], Token::Tag(tag) if tag.is("p")));
+}